@@ -2,7 +2,8 @@ use std::error::Error as StdError;
 
 use libuv_sys2::{self as ffi, uv_async_t};
 
-use crate::{Error, Handle};
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, Handle, LibuvError};
 
 type Callback = Box<dyn FnMut() -> Result<(), Box<dyn StdError>> + 'static>;
 
@@ -10,11 +11,21 @@ type Callback = Box<dyn FnMut() -> Result<(), Box<dyn StdError>> + 'static>;
 /// callback in the Neovim thread.
 ///
 /// [1]: http://docs.libuv.org/en/v1.x/async.html
-#[derive(Clone)]
 pub struct AsyncHandle {
     handle: Handle<uv_async_t, Callback>,
 }
 
+impl Clone for AsyncHandle {
+    /// Returns a new handle pointing to the same underlying libuv handle.
+    /// Every clone stays valid until [`close`](AsyncHandle::close) is called
+    /// on any one of them. After that none of the clones can be used, not even
+    /// to call [`is_closing`](AsyncHandle::is_closing) or `close` again.
+    fn clone(&self) -> Self {
+        let ptr = self.handle.as_ptr() as *mut uv_async_t;
+        Self { handle: unsafe { Handle::from_raw(ptr) } }
+    }
+}
+
 unsafe impl Send for AsyncHandle {}
 unsafe impl Sync for AsyncHandle {}
 
@@ -62,11 +73,29 @@ impl AsyncHandle {
             unsafe { ffi::uv_async_send(self.handle.as_ptr() as *mut _) };
 
         if retv < 0 {
-            return Err(Error::AsyncTrigger);
+            return Err(Error::AsyncTrigger(LibuvError::new(retv)));
         }
 
         Ok(())
     }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is
+    /// done with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and neither this handle
+    /// nor any of its clones can be used after calling this. That includes
+    /// querying them with [`is_closing`](AsyncHandle::is_closing) or closing
+    /// them again.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
 }
 
 extern "C" fn async_cb(ptr: *mut uv_async_t) {
@@ -77,8 +106,6 @@ extern "C" fn async_cb(ptr: *mut uv_async_t) {
     if !callback.is_null() {
         let callback = unsafe { &mut *callback };
 
-        if let Err(_err) = callback() {
-            // TODO: what now?
-        }
+        error_handler::call(HandleKind::Async, callback);
     }
 }
@@ -0,0 +1,132 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::error::Error as StdError;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{AsyncHandle, Error};
+
+/// The state shared between the senders and the receiving end of a channel.
+struct State<T> {
+    /// The messages that haven't been received yet.
+    queue: VecDeque<T>,
+
+    /// The number of live senders. Once it drops to zero the underlying
+    /// [`AsyncHandle`] is closed.
+    senders: usize,
+}
+
+/// The sending half of a channel created via [`channel`]. It can be cloned
+/// and sent to other threads.
+pub struct Sender<T> {
+    state: Arc<Mutex<State<T>>>,
+    handle: AsyncHandle,
+}
+
+impl<T> Sender<T> {
+    /// Sends a message to the main thread. Unlike [`AsyncHandle::send`] no
+    /// message is ever lost, even if the wakeups of the event loop coalesce.
+    pub fn send(&self, msg: T) -> Result<(), Error> {
+        let mut state = self.state.lock().unwrap();
+        state.queue.push_back(msg);
+        self.handle.send()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.state.lock().unwrap().senders += 1;
+        Self { state: Arc::clone(&self.state), handle: self.handle.clone() }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        // The lock is held while waking up the main thread so that the handle
+        // can't be closed under our feet.
+        let mut state = self.state.lock().unwrap();
+        state.senders -= 1;
+        if state.senders == 0 {
+            let _ = self.handle.send();
+        }
+    }
+}
+
+/// Creates a new channel whose messages are received on the main thread,
+/// returning its [`Sender`].
+///
+/// The callback is executed on the main thread once for every message, in
+/// the order they were sent. All the messages queued since the last wakeup of
+/// the event loop are received at once. The channel is closed once all the
+/// senders have been dropped.
+///
+/// NOTE: this function **must** be called from the main thread.
+///
+/// # Examples
+///
+/// ```ignore
+/// use std::thread;
+///
+/// use nvim_oxi::{self as oxi, libuv, print};
+///
+/// let sender = libuv::channel(|i: i32| {
+///     oxi::schedule(move |_| Ok(print!("Received {i}")));
+///     Ok::<_, oxi::Error>(())
+/// })?;
+///
+/// thread::spawn(move || {
+///     for i in 0..10 {
+///         sender.send(i).unwrap();
+///     }
+/// });
+/// ```
+pub fn channel<T, Cb, E>(mut callback: Cb) -> Result<Sender<T>, Error>
+where
+    T: Send + 'static,
+    Cb: FnMut(T) -> Result<(), E> + 'static,
+    E: StdError + 'static,
+{
+    let state =
+        Arc::new(Mutex::new(State { queue: VecDeque::new(), senders: 1 }));
+
+    // The handle has to be available inside its own callback to be able to
+    // close it, but it doesn't exist yet when the callback is created.
+    let this = Rc::new(Cell::new(None::<AsyncHandle>));
+
+    let handle = {
+        let state = Arc::clone(&state);
+        let this = Rc::clone(&this);
+
+        AsyncHandle::new(move || {
+            let msgs = {
+                let mut state = state.lock().unwrap();
+
+                // All the senders are gone, so nobody can use the handle
+                // anymore. It's freed once this callback returns.
+                if state.senders == 0 {
+                    if let Some(handle) = this.take() {
+                        unsafe { handle.close() };
+                    }
+                }
+
+                std::mem::take(&mut state.queue)
+            };
+
+            for msg in msgs {
+                // Each message is handled on its own so that an error or a
+                // panic doesn't drop the messages after it.
+                error_handler::call(HandleKind::Async, || {
+                    callback(msg).map_err(|err| Box::new(err) as _)
+                });
+            }
+
+            Ok::<_, Infallible>(())
+        })?
+    };
+
+    this.set(Some(handle.clone()));
+
+    Ok(Sender { state, handle })
+}
@@ -0,0 +1,101 @@
+use std::error::Error as StdError;
+
+use libuv_sys2::{self as ffi, uv_check_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, Handle, LibuvError};
+
+pub(crate) type Callback = Box<
+    dyn FnMut(&mut CheckHandle) -> Result<(), Box<dyn StdError>> + 'static,
+>;
+
+/// Binding to libuv's [Check handle][1] used to run a callback once per
+/// event loop iteration, right after polling for I/O.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/check.html
+pub struct CheckHandle {
+    handle: Handle<uv_check_t, Callback>,
+}
+
+impl CheckHandle {
+    fn new() -> Result<Self, Error> {
+        let handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_check_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        Ok(Self { handle })
+    }
+
+    /// Executes a callback once per event loop iteration, right after polling
+    /// for I/O.
+    pub fn start<Cb, E>(mut callback: Cb) -> Result<Self, Error>
+    where
+        Cb: FnMut(&mut Self) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut check = Self::new()?;
+
+        let callback: Callback = Box::new(move |check| {
+            // Type erase the callback by boxing its error.
+            callback(check).map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { check.handle.set_data(callback) };
+
+        let retv = unsafe {
+            ffi::uv_check_start(check.handle.as_mut_ptr(), Some(check_cb as _))
+        };
+
+        if retv < 0 {
+            unsafe { check.close() };
+            return Err(Error::CheckStart(LibuvError::new(retv)));
+        }
+
+        Ok(check)
+    }
+
+    /// Stops the handle, the callback will not be called anymore.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_check_stop(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::CheckStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is
+    /// done with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is active.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+extern "C" fn check_cb(ptr: *mut uv_check_t) {
+    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if !callback.is_null() {
+        let mut handle = CheckHandle { handle };
+        let callback = unsafe { &mut *callback };
+
+        error_handler::call(HandleKind::Check, || callback(&mut handle));
+    }
+}
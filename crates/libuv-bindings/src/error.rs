@@ -1,21 +1,171 @@
+use std::ffi::{c_char, CStr};
+use std::fmt;
+
+use libuv_sys2 as ffi;
 use thiserror::Error as ThisError;
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Debug, Eq, PartialEq, ThisError)]
 pub enum Error {
-    #[error("Couldn't trigger async handle")]
-    AsyncTrigger,
+    #[error("Couldn't trigger async handle: {0}")]
+    AsyncTrigger(LibuvError),
+
+    #[error("Couldn't start check handle: {0}")]
+    CheckStart(LibuvError),
+
+    #[error("Couldn't stop check handle: {0}")]
+    CheckStop(LibuvError),
+
+    #[error("Couldn't start watching file: {0}")]
+    FsEventStart(LibuvError),
+
+    #[error("Couldn't stop watching file: {0}")]
+    FsEventStop(LibuvError),
+
+    #[error("Couldn't watch file: {0}")]
+    FsEventWatch(LibuvError),
 
-    #[error("Couldn't initialize handle")]
-    HandleInit,
+    #[error("Couldn't start polling file: {0}")]
+    FsPollStart(LibuvError),
+
+    #[error("Couldn't stop polling file: {0}")]
+    FsPollStop(LibuvError),
+
+    #[error("Couldn't poll file: {0}")]
+    FsPollWatch(LibuvError),
+
+    #[error("Couldn't initialize handle: {0}")]
+    HandleInit(LibuvError),
 
     #[error("Couldn't allocate memory for a new handle")]
     HandleMemAlloc,
 
-    #[error("Couldn't start timer handle")]
-    TimerStart,
+    #[error("Couldn't start idle handle: {0}")]
+    IdleStart(LibuvError),
+
+    #[error("Couldn't stop idle handle: {0}")]
+    IdleStop(LibuvError),
+
+    #[error("Strings passed to libuv can't contain nul bytes")]
+    NulByte,
+
+    #[error("Couldn't bind pipe: {0}")]
+    PipeBind(LibuvError),
+
+    #[error("Couldn't start prepare handle: {0}")]
+    PrepareStart(LibuvError),
+
+    #[error("Couldn't stop prepare handle: {0}")]
+    PrepareStop(LibuvError),
+
+    #[error("Couldn't kill process: {0}")]
+    ProcessKill(LibuvError),
+
+    #[error("Couldn't spawn process: {0}")]
+    ProcessSpawn(LibuvError),
+
+    #[error("Couldn't start signal handle: {0}")]
+    SignalStart(LibuvError),
+
+    #[error("Couldn't stop signal handle: {0}")]
+    SignalStop(LibuvError),
+
+    #[error("Couldn't accept incoming connection: {0}")]
+    StreamAccept(LibuvError),
+
+    #[error("Couldn't connect stream: {0}")]
+    StreamConnect(LibuvError),
+
+    #[error("Couldn't listen for incoming connections: {0}")]
+    StreamListen(LibuvError),
+
+    #[error("Couldn't read from stream: {0}")]
+    StreamRead(LibuvError),
+
+    #[error("Couldn't start reading from stream: {0}")]
+    StreamReadStart(LibuvError),
+
+    #[error("Couldn't stop reading from stream: {0}")]
+    StreamReadStop(LibuvError),
+
+    #[error("Couldn't shut down stream: {0}")]
+    StreamShutdown(LibuvError),
+
+    #[error("Couldn't write to stream: {0}")]
+    StreamWrite(LibuvError),
+
+    #[error("Couldn't bind TCP handle: {0}")]
+    TcpBind(LibuvError),
+
+    #[error("Couldn't start timer handle: {0}")]
+    TimerStart(LibuvError),
+
+    #[error("Couldn't stop timer handle: {0}")]
+    TimerStop(LibuvError),
+
+    #[error("Work executed on the thread pool panicked: {0}")]
+    WorkPanic(String),
+
+    #[error("Couldn't queue work on the thread pool: {0}")]
+    WorkQueue(LibuvError),
+}
+
+/// An error code returned by a libuv function.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct LibuvError {
+    code: i32,
+}
+
+impl LibuvError {
+    #[inline]
+    pub(crate) fn new(code: i32) -> Self {
+        Self { code }
+    }
+
+    /// The raw error code, e.g. `-2` for `ENOENT` on Linux.
+    #[inline]
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
+    /// The name of the error, e.g. `"ENOENT"`.
+    pub fn name(&self) -> &'static str {
+        unsafe { to_str(ffi::uv_err_name(self.code)) }
+    }
+
+    /// A human readable description of the error, e.g. `"no such file or
+    /// directory"`.
+    pub fn message(&self) -> &'static str {
+        unsafe { to_str(ffi::uv_strerror(self.code)) }
+    }
+
+    /// Whether this error signals the end of a stream.
+    #[inline]
+    pub fn is_eof(&self) -> bool {
+        self.code == ffi::uv_errno_t_UV_EOF as _
+    }
+
+    /// Whether this error signals that the request was cancelled, e.g.
+    /// because its handle was closed.
+    #[inline]
+    pub fn is_canceled(&self) -> bool {
+        self.code == ffi::uv_errno_t_UV_ECANCELED as _
+    }
+}
+
+impl fmt::Display for LibuvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message(), self.name())
+    }
+}
+
+impl std::error::Error for LibuvError {}
 
-    #[error("Couldn't stop timer handle")]
-    TimerStop,
+/// Converts a static string returned by libuv into a `&str`.
+unsafe fn to_str(ptr: *const c_char) -> &'static str {
+    if ptr.is_null() {
+        return "";
+    }
+    CStr::from_ptr(ptr).to_str().unwrap_or_default()
 }
@@ -0,0 +1,164 @@
+use std::cell::RefCell;
+use std::error::Error as StdError;
+use std::ffi::c_int;
+use std::fmt;
+
+use luajit_bindings::{self as lua, ffi::*, macros::cstr};
+
+type ErrorHandler = Box<dyn Fn(CallbackError) + 'static>;
+
+thread_local! {
+    static ERROR_HANDLER: RefCell<Option<ErrorHandler>> = RefCell::new(None);
+}
+
+/// The kind of handle (or request) whose callback returned an error.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum HandleKind {
+    Async,
+    Check,
+    FsEvent,
+    FsPoll,
+    Idle,
+    Pipe,
+    Prepare,
+    Process,
+    Signal,
+    Tcp,
+    Timer,
+    Work,
+}
+
+impl fmt::Display for HandleKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Async => "async",
+            Self::Check => "check",
+            Self::FsEvent => "fs_event",
+            Self::FsPoll => "fs_poll",
+            Self::Idle => "idle",
+            Self::Pipe => "pipe",
+            Self::Prepare => "prepare",
+            Self::Process => "process",
+            Self::Signal => "signal",
+            Self::Tcp => "tcp",
+            Self::Timer => "timer",
+            Self::Work => "work",
+        })
+    }
+}
+
+/// An error returned by the callback registered on a handle.
+#[derive(Debug)]
+pub struct CallbackError {
+    /// The kind of handle the callback was registered on.
+    pub kind: HandleKind,
+
+    /// The error returned by the callback.
+    pub error: Box<dyn StdError>,
+}
+
+impl fmt::Display for CallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error in {} callback: {}", self.kind, self.error)
+    }
+}
+
+impl StdError for CallbackError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+/// Installs a global handler which is called with every error returned by the
+/// callbacks registered on the handles of this crate, replacing the previous
+/// one.
+///
+/// If no handler is installed the errors are reported to the user via
+/// `vim.notify` on the next iteration of the event loop.
+///
+/// NOTE: this function **must** be called from the main thread.
+pub fn set_error_handler<F>(handler: F)
+where
+    F: Fn(CallbackError) + 'static,
+{
+    ERROR_HANDLER.with(|h| *h.borrow_mut() = Some(Box::new(handler)));
+}
+
+/// Removes the handler installed via [`set_error_handler`], going back to
+/// the default behaviour.
+pub fn reset_error_handler() {
+    ERROR_HANDLER.with(|h| *h.borrow_mut() = None);
+}
+
+/// Calls a callback registered on a handle from within an `extern "C"`
+/// function, reporting both the errors it returns and the panics it raises.
+pub(crate) fn call<F>(kind: HandleKind, callback: F)
+where
+    F: FnOnce() -> Result<(), Box<dyn StdError>>,
+{
+    match lua::panic::catch(callback) {
+        Ok(Ok(())) => {},
+        Ok(Err(err)) => report(kind, err),
+        Err(panic) => report(kind, Box::new(panic)),
+    }
+}
+
+/// Reports an error returned by a callback to the current error handler.
+pub(crate) fn report(kind: HandleKind, error: Box<dyn StdError>) {
+    let error = CallbackError { kind, error };
+
+    // The handler is taken out of the cell while it's being called so that it
+    // can install a new handler without a double borrow.
+    let handler = ERROR_HANDLER.with(|h| h.borrow_mut().take());
+
+    match handler {
+        Some(handler) => {
+            handler(error);
+
+            ERROR_HANDLER.with(|h| {
+                let mut h = h.borrow_mut();
+                if h.is_none() {
+                    *h = Some(handler);
+                }
+            });
+        },
+
+        None => notify(&error.to_string()),
+    }
+}
+
+/// Schedules a call to `vim.notify` with the given error message. Neovim's
+/// API can't be called directly from a libuv callback.
+fn notify(msg: &str) {
+    unsafe extern "C" fn notify_cb(lstate: *mut lua_State) -> c_int {
+        lua_getglobal(lstate, cstr!("vim"));
+        lua_getfield(lstate, -1, cstr!("notify"));
+        lua_pushvalue(lstate, lua_upvalueindex(1));
+        // `vim.log.levels.ERROR`.
+        lua_pushinteger(lstate, 4);
+        lua_call(lstate, 2, 0);
+        lua_pop(lstate, 1);
+        0
+    }
+
+    unsafe {
+        lua::with_state(move |lstate| {
+            // Put `vim.schedule` on the stack.
+            lua_getglobal(lstate, cstr!("vim"));
+            lua_getfield(lstate, -1, cstr!("schedule"));
+
+            // Put a closure over the message on the stack.
+            lua_pushlstring(lstate, msg.as_ptr() as *const _, msg.len());
+            lua_pushcclosure(lstate, notify_cb, 1);
+
+            // If this fails there's nowhere left to report the error to.
+            if lua_pcall(lstate, 1, 0, 0) != LUA_OK {
+                lua_pop(lstate, 1);
+            }
+
+            // Pop `vim` off the stack.
+            lua_pop(lstate, 1);
+        })
+    };
+}
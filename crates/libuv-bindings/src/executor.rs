@@ -0,0 +1,309 @@
+//! A single-threaded executor for [`Future`]s driven by the Neovim event
+//! loop.
+//!
+//! Futures spawned with [`spawn`] are always polled on the main thread, which
+//! means they can freely call functions from the Neovim API without having to
+//! go through `schedule`.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use std::time::Duration;
+//!
+//! use nvim_oxi::{self as oxi, api, libuv::executor};
+//!
+//! #[oxi::module]
+//! fn foo() -> oxi::Result<()> {
+//!     executor::spawn(async {
+//!         executor::sleep(Duration::from_secs(1)).await?;
+//!         api::out_write("Hello from the future!\n");
+//!         Ok::<_, oxi::libuv::Error>(())
+//!     })?;
+//!
+//!     Ok(())
+//! }
+//! ```
+
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::time::Duration;
+
+use once_cell::unsync::OnceCell;
+
+use crate::{AsyncHandle, Error, TimerHandle};
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()> + 'static>>;
+
+thread_local! {
+    static EXECUTOR: OnceCell<Executor> = const { OnceCell::new() };
+}
+
+/// The executor's state, living on the main thread.
+struct Executor {
+    /// The tasks that haven't completed yet, indexed by their id.
+    tasks: RefCell<HashMap<usize, LocalFuture>>,
+
+    /// The id that'll be assigned to the next spawned task.
+    next_id: Cell<usize>,
+
+    /// The queue of tasks that are ready to be polled.
+    queue: Arc<ReadyQueue>,
+}
+
+/// The ids of the tasks that have been woken up, together with the handle
+/// used to wake up the Neovim event loop. Wakers can be sent to other threads,
+/// so this has to be `Send + Sync`.
+struct ReadyQueue {
+    ids: Mutex<VecDeque<usize>>,
+    handle: AsyncHandle,
+}
+
+impl ReadyQueue {
+    fn push(&self, id: usize) {
+        self.ids.lock().unwrap().push_back(id);
+
+        // The only way this can fail is if the handle has been closed, in
+        // which case there's nothing left to wake up anyway.
+        let _ = self.handle.send();
+    }
+
+    fn drain(&self) -> VecDeque<usize> {
+        std::mem::take(&mut *self.ids.lock().unwrap())
+    }
+}
+
+/// A [`Wake`] implementation which schedules a single task to be polled on
+/// the next iteration of the event loop.
+struct TaskWaker {
+    id: usize,
+    queue: Arc<ReadyQueue>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.queue.push(self.id)
+    }
+}
+
+impl Executor {
+    fn new() -> Result<Self, Error> {
+        let handle = AsyncHandle::new(|| {
+            with_executor(Executor::run);
+            Ok::<_, Infallible>(())
+        })?;
+
+        let queue = ReadyQueue { ids: Mutex::default(), handle };
+
+        Ok(Self {
+            tasks: RefCell::default(),
+            next_id: Cell::new(0),
+            queue: Arc::new(queue),
+        })
+    }
+
+    fn spawn(&self, future: LocalFuture) {
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+        self.tasks.borrow_mut().insert(id, future);
+        self.queue.push(id);
+    }
+
+    /// Polls all the tasks that have been woken up since the last run.
+    fn run(&self) {
+        for id in self.queue.drain() {
+            // The future is taken out of the map while it's being polled so
+            // that it can spawn new tasks without a double borrow. It could
+            // also be missing if the same task was woken up more than once.
+            let mut future = match self.tasks.borrow_mut().remove(&id) {
+                Some(future) => future,
+                None => continue,
+            };
+
+            let waker = Waker::from(Arc::new(TaskWaker {
+                id,
+                queue: Arc::clone(&self.queue),
+            }));
+
+            let mut cx = Context::from_waker(&waker);
+
+            if future.as_mut().poll(&mut cx).is_pending() {
+                self.tasks.borrow_mut().insert(id, future);
+            }
+        }
+    }
+}
+
+/// Executes a function with access to the executor.
+///
+/// NOTE: this will panic if the executor hasn't been initialized by calling
+/// [`spawn`].
+fn with_executor<F, R>(fun: F) -> R
+where
+    F: FnOnce(&Executor) -> R,
+{
+    EXECUTOR.with(move |executor| match executor.get() {
+        Some(executor) => fun(executor),
+        None => unreachable!("the executor is initialized by `spawn`"),
+    })
+}
+
+/// Spawns a new future on the Neovim event loop, returning a [`Task`] which
+/// can be awaited to get its output.
+///
+/// The future will be polled for the first time on the next iteration of the
+/// event loop. Dropping the returned [`Task`] detaches the future instead of
+/// cancelling it.
+///
+/// NOTE: this function **must** be called from the main thread.
+pub fn spawn<F>(future: F) -> Result<Task<F::Output>, Error>
+where
+    F: Future + 'static,
+    F::Output: 'static,
+{
+    EXECUTOR.with(|executor| {
+        executor.get_or_try_init(Executor::new).map(|_| ())
+    })?;
+
+    let state = Rc::new(RefCell::new(TaskState { output: None, waker: None }));
+
+    let task = Task { state: Rc::clone(&state) };
+
+    let future = Box::pin(async move {
+        let output = future.await;
+        let mut state = state.borrow_mut();
+        state.output = Some(output);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    });
+
+    with_executor(move |executor| executor.spawn(future));
+
+    Ok(task)
+}
+
+struct TaskState<T> {
+    output: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// A handle to a future spawned via [`spawn`] which resolves to the output of
+/// that future.
+#[must_use = "dropping a `Task` detaches it, use `let _ = ..` to silence this \
+              warning"]
+pub struct Task<T> {
+    state: Rc<RefCell<TaskState<T>>>,
+}
+
+impl<T> Future for Task<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.borrow_mut();
+
+        match state.output.take() {
+            Some(output) => Poll::Ready(output),
+
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// Returns a future which completes after `duration` has elapsed. It's built
+/// on top of a [`TimerHandle`], so it doesn't block the Neovim event loop.
+///
+/// The future resolves to an error if the underlying timer couldn't be
+/// started.
+pub fn sleep(duration: Duration) -> Sleep {
+    Sleep { duration, state: None }
+}
+
+/// Future returned by [`sleep`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Sleep {
+    duration: Duration,
+    state: Option<SleepState>,
+}
+
+struct SleepState {
+    timer: TimerHandle,
+    shared: Rc<RefCell<SleepShared>>,
+}
+
+#[derive(Default)]
+struct SleepShared {
+    elapsed: bool,
+    waker: Option<Waker>,
+}
+
+impl Future for Sleep {
+    type Output = Result<(), Error>;
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        if let Some(state) = &self.state {
+            let mut shared = state.shared.borrow_mut();
+
+            if shared.elapsed {
+                return Poll::Ready(Ok(()));
+            }
+
+            shared.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+
+        let shared = Rc::new(RefCell::new(SleepShared {
+            elapsed: false,
+            waker: Some(cx.waker().clone()),
+        }));
+
+        let timer = {
+            let shared = Rc::clone(&shared);
+            // The timer isn't a one-shot one since it has to be closed when
+            // the future is dropped, even if it hasn't fired yet.
+            TimerHandle::start(self.duration, Duration::ZERO, move |_| {
+                let mut shared = shared.borrow_mut();
+                shared.elapsed = true;
+                if let Some(waker) = shared.waker.take() {
+                    waker.wake();
+                }
+                Ok::<_, Infallible>(())
+            })
+        };
+
+        match timer {
+            Ok(timer) => {
+                self.state = Some(SleepState { timer, shared });
+                Poll::Pending
+            },
+
+            // If we couldn't start the timer there's no way to ever wake up
+            // the task, so we report the error instead of hanging forever.
+            Err(err) => Poll::Ready(Err(err)),
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        // Closing the timer also stops it if it hasn't fired yet.
+        if let Some(state) = self.state.take() {
+            unsafe { state.timer.close() };
+        }
+    }
+}
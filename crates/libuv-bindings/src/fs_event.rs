@@ -0,0 +1,187 @@
+use std::error::Error as StdError;
+use std::ffi::{c_char, c_int};
+use std::path::{Path, PathBuf};
+
+use libuv_sys2::{self as ffi, uv_fs_event_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{utils, Error, Handle, LibuvError};
+
+pub(crate) type Callback = Box<
+    dyn FnMut(
+            &mut FsEventHandle,
+            Result<FsEvent, Error>,
+        ) -> Result<(), Box<dyn StdError>>
+        + 'static,
+>;
+
+/// Binding to libuv's [FS Event handle][1] used to watch a file or a
+/// directory for changes.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/fs_event.html
+pub struct FsEventHandle {
+    handle: Handle<uv_fs_event_t, Callback>,
+}
+
+/// The kind of change reported by an [`FsEventHandle`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FsEventKind {
+    /// The file was renamed, created or deleted.
+    Rename,
+
+    /// The contents or the metadata of the file changed.
+    Change,
+}
+
+/// A change detected by an [`FsEventHandle`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FsEvent {
+    /// The name of the file that changed. If a directory is being watched
+    /// this is relative to that directory. It can be `None` on platforms
+    /// where libuv can't detect which file changed.
+    pub filename: Option<PathBuf>,
+
+    pub kind: FsEventKind,
+}
+
+impl FsEventHandle {
+    fn new() -> Result<Self, Error> {
+        let handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_fs_event_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        Ok(Self { handle })
+    }
+
+    /// Starts watching `path` for changes, executing the callback every time
+    /// a change is detected.
+    ///
+    /// If `recursive` is `true` the subdirectories of `path` are watched as
+    /// well. NOTE: this is currently only supported on macOS and Windows.
+    pub fn start<P, Cb, E>(
+        path: P,
+        recursive: bool,
+        mut callback: Cb,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        Cb: FnMut(&mut Self, Result<FsEvent, Error>) -> Result<(), E>
+            + 'static,
+        E: StdError + 'static,
+    {
+        let path = utils::to_cstring(path.as_ref().as_os_str())?;
+
+        let mut fs_event = Self::new()?;
+
+        let callback: Callback = Box::new(move |fs_event, event| {
+            // Type erase the callback by boxing its error.
+            callback(fs_event, event)
+                .map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { fs_event.handle.set_data(callback) };
+
+        let flags = if recursive {
+            ffi::uv_fs_event_flags_UV_FS_EVENT_RECURSIVE
+        } else {
+            0
+        };
+
+        let retv = unsafe {
+            ffi::uv_fs_event_start(
+                fs_event.handle.as_mut_ptr(),
+                Some(fs_event_cb as _),
+                path.as_ptr(),
+                flags as _,
+            )
+        };
+
+        if retv < 0 {
+            unsafe { fs_event.close() };
+            return Err(Error::FsEventStart(LibuvError::new(retv)));
+        }
+
+        Ok(fs_event)
+    }
+
+    /// Stops watching for changes.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_fs_event_stop(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::FsEventStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is done
+    /// with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is active.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+extern "C" fn fs_event_cb(
+    ptr: *mut uv_fs_event_t,
+    filename: *const c_char,
+    events: c_int,
+    status: c_int,
+) {
+    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if callback.is_null() {
+        return;
+    }
+
+    let mut handle = FsEventHandle { handle };
+    let callback = unsafe { &mut *callback };
+
+    if status < 0 {
+        let err = Error::FsEventWatch(LibuvError::new(status));
+
+        error_handler::call(HandleKind::FsEvent, || {
+            callback(&mut handle, Err(err))
+        });
+
+        return;
+    }
+
+    let filename = unsafe { utils::to_path(filename) };
+
+    let kinds = [
+        (ffi::uv_fs_event_UV_RENAME, FsEventKind::Rename),
+        (ffi::uv_fs_event_UV_CHANGE, FsEventKind::Change),
+    ];
+
+    // A single event can carry more than one kind of change, in which case
+    // the callback is called once for each of them.
+    for (flag, kind) in kinds {
+        if events & flag as c_int == 0 {
+            continue;
+        }
+
+        let event = FsEvent { filename: filename.clone(), kind };
+
+        error_handler::call(HandleKind::FsEvent, || {
+            callback(&mut handle, Ok(event))
+        });
+    }
+}
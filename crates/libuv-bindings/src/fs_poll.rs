@@ -0,0 +1,186 @@
+use std::error::Error as StdError;
+use std::ffi::c_int;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use libuv_sys2::{self as ffi, uv_fs_poll_t, uv_stat_t, uv_timespec_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{utils, Error, Handle, LibuvError};
+
+pub(crate) type Callback = Box<
+    dyn FnMut(
+            &mut FsPollHandle,
+            Result<(FsStat, FsStat), Error>,
+        ) -> Result<(), Box<dyn StdError>>
+        + 'static,
+>;
+
+/// Binding to libuv's [FS Poll handle][1] used to watch a file for changes by
+/// periodically calling `stat` on it. Unlike [`FsEventHandle`] this also
+/// works on file systems where change notifications aren't available, e.g.
+/// network file systems.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/fs_poll.html
+/// [`FsEventHandle`]: crate::FsEventHandle
+pub struct FsPollHandle {
+    handle: Handle<uv_fs_poll_t, Callback>,
+}
+
+/// A subset of the metadata of a file, as returned by `stat`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FsStat {
+    /// The size of the file in bytes.
+    pub size: u64,
+
+    /// The permissions and type of the file.
+    pub mode: u64,
+
+    /// The time the file was last accessed.
+    pub accessed: SystemTime,
+
+    /// The time the file was last modified.
+    pub modified: SystemTime,
+}
+
+impl From<&uv_stat_t> for FsStat {
+    fn from(stat: &uv_stat_t) -> Self {
+        Self {
+            size: stat.st_size,
+            mode: stat.st_mode,
+            accessed: to_system_time(&stat.st_atim),
+            modified: to_system_time(&stat.st_mtim),
+        }
+    }
+}
+
+impl FsPollHandle {
+    fn new() -> Result<Self, Error> {
+        let handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_fs_poll_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        Ok(Self { handle })
+    }
+
+    /// Starts checking `path` for changes every `interval`, executing the
+    /// callback with the previous and the current stats of the file every
+    /// time a change is detected.
+    pub fn start<P, Cb, E>(
+        path: P,
+        interval: Duration,
+        mut callback: Cb,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        Cb: FnMut(&mut Self, Result<(FsStat, FsStat), Error>) -> Result<(), E>
+            + 'static,
+        E: StdError + 'static,
+    {
+        let path = utils::to_cstring(path.as_ref().as_os_str())?;
+
+        let mut fs_poll = Self::new()?;
+
+        let callback: Callback = Box::new(move |fs_poll, stats| {
+            // Type erase the callback by boxing its error.
+            callback(fs_poll, stats)
+                .map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { fs_poll.handle.set_data(callback) };
+
+        let retv = unsafe {
+            ffi::uv_fs_poll_start(
+                fs_poll.handle.as_mut_ptr(),
+                Some(fs_poll_cb as _),
+                path.as_ptr(),
+                interval.as_millis() as _,
+            )
+        };
+
+        if retv < 0 {
+            unsafe { fs_poll.close() };
+            return Err(Error::FsPollStart(LibuvError::new(retv)));
+        }
+
+        Ok(fs_poll)
+    }
+
+    /// Stops checking for changes.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_fs_poll_stop(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::FsPollStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is done
+    /// with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is active.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+/// Converts a libuv timestamp to a `SystemTime`. Timestamps before 1970 have
+/// a negative number of seconds, and those that can't be represented on the
+/// current platform fall back to the Unix epoch.
+fn to_system_time(time: &uv_timespec_t) -> SystemTime {
+    let epoch = SystemTime::UNIX_EPOCH;
+    let secs = Duration::from_secs(time.tv_sec.unsigned_abs() as _);
+    let nanos =
+        Duration::from_nanos(time.tv_nsec.clamp(0, 999_999_999) as u64);
+
+    let time = if time.tv_sec < 0 {
+        epoch.checked_sub(secs)
+    } else {
+        epoch.checked_add(secs)
+    };
+
+    time.and_then(|time| time.checked_add(nanos)).unwrap_or(epoch)
+}
+
+extern "C" fn fs_poll_cb(
+    ptr: *mut uv_fs_poll_t,
+    status: c_int,
+    prev: *const uv_stat_t,
+    curr: *const uv_stat_t,
+) {
+    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if !callback.is_null() {
+        let mut handle = FsPollHandle { handle };
+        let callback = unsafe { &mut *callback };
+
+        error_handler::call(HandleKind::FsPoll, || {
+            // The conversion happens in here so that a panic can't unwind
+            // across the FFI boundary.
+            let stats = if status < 0 {
+                Err(Error::FsPollWatch(LibuvError::new(status)))
+            } else {
+                unsafe { Ok((FsStat::from(&*prev), FsStat::from(&*curr))) }
+            };
+
+            callback(&mut handle, stats)
+        });
+    }
+}
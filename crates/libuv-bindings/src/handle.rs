@@ -1,10 +1,23 @@
 use std::alloc::{self, Layout};
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::marker::PhantomData;
 
 use libuv_sys2::{self as ffi, uv_handle_t, uv_loop_t};
 
-use crate::{Error, Result};
+use crate::{Error, LibuvError, Result};
+
+thread_local! {
+    /// The number of handles that have been allocated but not freed yet.
+    static LIVE_HANDLES: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Returns the number of handles that have been allocated but not freed yet.
+/// It's only meant to be used in tests.
+#[doc(hidden)]
+pub fn live_handles() -> usize {
+    LIVE_HANDLES.with(Cell::get)
+}
 
 /// TODO: docs
 pub(crate) struct Handle<T, D: 'static> {
@@ -12,39 +25,87 @@ pub(crate) struct Handle<T, D: 'static> {
     data: PhantomData<D>,
 }
 
-impl<T, D> Clone for Handle<T, D> {
-    fn clone(&self) -> Self {
-        Self { ptr: self.ptr, data: PhantomData }
-    }
-}
-
 impl<T, D> Handle<T, D> {
     /// TODO: docs
     pub(crate) fn new<I>(initializer: I) -> Result<Handle<T, D>>
     where
         I: FnOnce(*mut uv_loop_t, &mut Self) -> i32,
     {
-        let layout = Layout::new::<T>();
-        let ptr = unsafe { alloc::alloc(layout) as *mut T };
-
-        if ptr.is_null() {
-            return Err(Error::HandleMemAlloc);
-        }
-
-        let mut handle = Self { ptr, data: PhantomData };
+        let mut handle = Self::alloc()?;
 
         let retv = unsafe {
             crate::with_loop(|uv_loop| initializer(uv_loop, &mut handle))
         };
 
         if retv < 0 {
-            unsafe { alloc::dealloc(ptr as *mut u8, layout) };
-            return Err(Error::HandleInit);
+            unsafe { handle.dealloc() };
+            return Err(Error::HandleInit(LibuvError::new(retv)));
         }
 
         Ok(handle)
     }
 
+    /// Allocates the memory for a new handle without initializing it. The
+    /// memory is zeroed so that the handle's data starts out as a null
+    /// pointer.
+    pub(crate) fn alloc() -> Result<Handle<T, D>> {
+        let ptr = unsafe { alloc::alloc_zeroed(Layout::new::<T>()) as *mut T };
+
+        if ptr.is_null() {
+            return Err(Error::HandleMemAlloc);
+        }
+
+        LIVE_HANDLES.with(|n| n.set(n.get() + 1));
+
+        Ok(Self { ptr, data: PhantomData })
+    }
+
+    /// Frees the memory of a handle that was never registered on the loop.
+    unsafe fn dealloc(self) {
+        LIVE_HANDLES.with(|n| n.set(n.get() - 1));
+        alloc::dealloc(self.ptr as *mut u8, Layout::new::<T>())
+    }
+
+    /// Closes a handle that has been registered on the loop. Both the handle
+    /// and its data are freed once libuv is done with it, so the handle (and
+    /// any other handle pointing to the same memory) must not be used after
+    /// calling this.
+    pub(crate) unsafe fn close(self) {
+        // This only catches handles that are still closing. Once `close_cb`
+        // has run the memory is gone and nothing can be checked anymore.
+        if self.is_closing() {
+            return;
+        }
+
+        ffi::uv_close(
+            self.ptr as *mut uv_handle_t,
+            Some(close_cb::<T, D> as _),
+        )
+    }
+
+    /// Returns a guard which closes the handle once it's dropped, even if
+    /// that happens while unwinding from a panic. Used by the one-shot
+    /// handles, which close themselves after their callback returns.
+    ///
+    /// NOTE: the handle can't be used once the guard has been dropped.
+    pub(crate) unsafe fn close_on_drop(&self) -> CloseOnDrop<T, D> {
+        CloseOnDrop(Self::from_raw(self.ptr))
+    }
+
+    /// Returns `true` if the handle is active. What that means depends on the
+    /// type of the handle, e.g. a timer is active if it's been started and
+    /// not yet stopped.
+    pub(crate) fn is_active(&self) -> bool {
+        unsafe { ffi::uv_is_active(self.as_ptr() as *const uv_handle_t) != 0 }
+    }
+
+    /// Returns `true` if the handle is closing, i.e. it's been closed but
+    /// libuv isn't done with it yet. This must not be called once the handle
+    /// has been freed.
+    pub(crate) fn is_closing(&self) -> bool {
+        unsafe { ffi::uv_is_closing(self.as_ptr() as *const uv_handle_t) != 0 }
+    }
+
     pub(crate) fn as_ptr(&self) -> *const T {
         self.ptr.cast()
     }
@@ -61,7 +122,15 @@ impl<T, D> Handle<T, D> {
         ffi::uv_handle_get_data(self.as_ptr() as *const uv_handle_t) as *mut D
     }
 
+    /// Sets the data of the handle, dropping the previous one if there was
+    /// any.
     pub(crate) unsafe fn set_data(&mut self, data: D) {
+        let old = self.get_data();
+
+        if !old.is_null() {
+            drop(Box::from_raw(old));
+        }
+
         let data = Box::into_raw(Box::new(data));
 
         ffi::uv_handle_set_data(
@@ -70,3 +139,24 @@ impl<T, D> Handle<T, D> {
         )
     }
 }
+
+/// Returned by [`Handle::close_on_drop`].
+pub(crate) struct CloseOnDrop<T, D: 'static>(Handle<T, D>);
+
+impl<T, D> Drop for CloseOnDrop<T, D> {
+    fn drop(&mut self) {
+        unsafe { Handle::<T, D>::from_raw(self.0.ptr).close() }
+    }
+}
+
+extern "C" fn close_cb<T, D: 'static>(ptr: *mut uv_handle_t) {
+    let handle: Handle<T, D> = unsafe { Handle::from_raw(ptr as *mut T) };
+
+    let data = unsafe { handle.get_data() };
+
+    if !data.is_null() {
+        drop(unsafe { Box::from_raw(data) });
+    }
+
+    unsafe { handle.dealloc() }
+}
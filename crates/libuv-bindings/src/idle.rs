@@ -0,0 +1,104 @@
+use std::error::Error as StdError;
+
+use libuv_sys2::{self as ffi, uv_idle_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, Handle, LibuvError};
+
+pub(crate) type Callback =
+    Box<dyn FnMut(&mut IdleHandle) -> Result<(), Box<dyn StdError>> + 'static>;
+
+/// Binding to libuv's [Idle handle][1] used to run a callback once
+/// per event loop iteration, right before the loop blocks waiting for I/O.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/idle.html
+pub struct IdleHandle {
+    handle: Handle<uv_idle_t, Callback>,
+}
+
+impl IdleHandle {
+    fn new() -> Result<Self, Error> {
+        let handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_idle_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        Ok(Self { handle })
+    }
+
+    /// Executes a callback once per event loop iteration, before the prepare
+    /// handles.
+    ///
+    /// NOTE: while there are active idle handles the loop will perform a zero
+    /// timeout poll instead of blocking for I/O, so this should be stopped as
+    /// soon as it's not needed anymore.
+    pub fn start<Cb, E>(mut callback: Cb) -> Result<Self, Error>
+    where
+        Cb: FnMut(&mut Self) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut idle = Self::new()?;
+
+        let callback: Callback = Box::new(move |idle| {
+            // Type erase the callback by boxing its error.
+            callback(idle).map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { idle.handle.set_data(callback) };
+
+        let retv = unsafe {
+            ffi::uv_idle_start(idle.handle.as_mut_ptr(), Some(idle_cb as _))
+        };
+
+        if retv < 0 {
+            unsafe { idle.close() };
+            return Err(Error::IdleStart(LibuvError::new(retv)));
+        }
+
+        Ok(idle)
+    }
+
+    /// Stops the handle, the callback will not be called anymore.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_idle_stop(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::IdleStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is
+    /// done with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is active.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+extern "C" fn idle_cb(ptr: *mut uv_idle_t) {
+    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if !callback.is_null() {
+        let mut handle = IdleHandle { handle };
+        let callback = unsafe { &mut *callback };
+
+        error_handler::call(HandleKind::Idle, || callback(&mut handle));
+    }
+}
@@ -1,13 +1,48 @@
 mod r#async;
+mod channel;
+mod check;
 mod error;
+mod error_handler;
+pub mod executor;
+mod fs_event;
+mod fs_poll;
 mod handle;
+mod idle;
 mod r#loop;
+mod pipe;
+mod prepare;
+mod process;
+mod signal;
+mod stream;
+mod tcp;
 mod timer;
+mod utils;
+mod work;
 
-pub use error::Error;
+pub use channel::{channel, Sender};
+pub use check::CheckHandle;
 use error::Result;
+pub use error::{Error, LibuvError};
+pub use error_handler::{
+    reset_error_handler,
+    set_error_handler,
+    CallbackError,
+    HandleKind,
+};
+pub use fs_event::{FsEvent, FsEventHandle, FsEventKind};
+pub use fs_poll::{FsPollHandle, FsStat};
+#[doc(hidden)]
+pub use handle::live_handles;
 use handle::Handle;
+pub use idle::IdleHandle;
+pub use pipe::PipeHandle;
+pub use prepare::PrepareHandle;
+pub use process::{ProcessHandle, ProcessOpts, ProcessOptsBuilder, Stdio};
 pub use r#async::AsyncHandle;
 pub use r#loop::init;
 use r#loop::with_loop;
+pub use signal::SignalHandle;
+pub use stream::StreamHandle;
+pub use tcp::TcpHandle;
 pub use timer::TimerHandle;
+pub use work::queue_work;
@@ -0,0 +1,98 @@
+use std::error::Error as StdError;
+use std::path::Path;
+
+use libuv_sys2::{self as ffi, uv_pipe_t, uv_stream_t};
+
+use crate::error_handler::HandleKind;
+use crate::stream::{connect_cb, sealed, ConnectReq, StreamData};
+use crate::{utils, Error, Handle, LibuvError, StreamHandle};
+
+/// Binding to libuv's [Pipe handle][1], which provides an abstraction over
+/// Unix domain sockets and Windows named pipes. It's also used to
+/// communicate with the standard streams of a child process.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/pipe.html
+pub struct PipeHandle {
+    handle: Handle<uv_pipe_t, StreamData<Self>>,
+}
+
+impl PipeHandle {
+    /// Binds a new pipe to a file path (Unix) or a name (Windows), returning
+    /// a handle which can start listening for incoming connections with
+    /// [`listen`](StreamHandle::listen).
+    pub fn bind<P: AsRef<Path>>(name: P) -> Result<Self, Error> {
+        let name = utils::to_cstring(name.as_ref().as_os_str())?;
+
+        let mut pipe = <Self as sealed::Stream>::init()?;
+
+        let retv = unsafe {
+            ffi::uv_pipe_bind(pipe.handle.as_mut_ptr(), name.as_ptr())
+        };
+
+        if retv < 0 {
+            unsafe { StreamHandle::close(pipe) };
+            return Err(Error::PipeBind(LibuvError::new(retv)));
+        }
+
+        Ok(pipe)
+    }
+
+    /// Connects to the Unix domain socket or named pipe at `name`, executing
+    /// the callback once the connection is established or has failed.
+    pub fn connect<P, Cb, E>(name: P, callback: Cb) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        Cb: FnOnce(&mut Self, Result<(), Error>) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let name = utils::to_cstring(name.as_ref().as_os_str())?;
+
+        let mut pipe = <Self as sealed::Stream>::init()?;
+
+        let req = ConnectReq::alloc(&pipe, callback);
+
+        // Errors are reported to the connect callback.
+        unsafe {
+            ffi::uv_pipe_connect(
+                req,
+                pipe.handle.as_mut_ptr(),
+                name.as_ptr(),
+                Some(connect_cb::<Self> as _),
+            )
+        };
+
+        Ok(pipe)
+    }
+}
+
+impl sealed::Stream for PipeHandle {
+    const KIND: HandleKind = HandleKind::Pipe;
+
+    fn init() -> Result<Self, Error> {
+        let mut handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_pipe_init(uv_loop, handle.as_mut_ptr(), 0)
+        })?;
+
+        unsafe { handle.set_data(StreamData::default()) };
+
+        Ok(Self { handle })
+    }
+
+    fn as_stream_ptr(&self) -> *mut uv_stream_t {
+        self.handle.as_ptr() as *mut uv_stream_t
+    }
+
+    unsafe fn from_stream_ptr(ptr: *mut uv_stream_t) -> Self {
+        Self { handle: Handle::from_raw(ptr as *mut uv_pipe_t) }
+    }
+
+    fn data(&self) -> *mut StreamData<Self> {
+        unsafe { self.handle.get_data() }
+    }
+}
+
+impl StreamHandle for PipeHandle {
+    unsafe fn close(self) {
+        self.handle.close()
+    }
+}
@@ -0,0 +1,104 @@
+use std::error::Error as StdError;
+
+use libuv_sys2::{self as ffi, uv_prepare_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, Handle, LibuvError};
+
+pub(crate) type Callback = Box<
+    dyn FnMut(&mut PrepareHandle) -> Result<(), Box<dyn StdError>> + 'static,
+>;
+
+/// Binding to libuv's [Prepare handle][1] used to run a callback once
+/// per event loop iteration, right before polling for I/O.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/prepare.html
+pub struct PrepareHandle {
+    handle: Handle<uv_prepare_t, Callback>,
+}
+
+impl PrepareHandle {
+    fn new() -> Result<Self, Error> {
+        let handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_prepare_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        Ok(Self { handle })
+    }
+
+    /// Executes a callback once per event loop iteration, right before polling
+    /// for I/O.
+    pub fn start<Cb, E>(mut callback: Cb) -> Result<Self, Error>
+    where
+        Cb: FnMut(&mut Self) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut prepare = Self::new()?;
+
+        let callback: Callback = Box::new(move |prepare| {
+            // Type erase the callback by boxing its error.
+            callback(prepare).map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { prepare.handle.set_data(callback) };
+
+        let retv = unsafe {
+            ffi::uv_prepare_start(
+                prepare.handle.as_mut_ptr(),
+                Some(prepare_cb as _),
+            )
+        };
+
+        if retv < 0 {
+            unsafe { prepare.close() };
+            return Err(Error::PrepareStart(LibuvError::new(retv)));
+        }
+
+        Ok(prepare)
+    }
+
+    /// Stops the handle, the callback will not be called anymore.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_prepare_stop(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::PrepareStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is
+    /// done with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is active.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+extern "C" fn prepare_cb(ptr: *mut uv_prepare_t) {
+    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if !callback.is_null() {
+        let mut handle = PrepareHandle { handle };
+        let callback = unsafe { &mut *callback };
+
+        error_handler::call(HandleKind::Prepare, || callback(&mut handle));
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::ffi::{c_char, c_int, CString, OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+use libuv_sys2::{self as ffi, uv_process_t, uv_stdio_container_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::stream::sealed::Stream;
+use crate::utils::to_cstring;
+use crate::{Error, Handle, LibuvError, PipeHandle, StreamHandle};
+
+pub(crate) type Callback =
+    Box<dyn FnOnce(i64, i32) -> Result<(), Box<dyn StdError>> + 'static>;
+
+/// Binding to libuv's [Process handle][1] used to spawn child processes
+/// without blocking the Neovim event loop.
+///
+/// The standard streams of the child are exposed as [`PipeHandle`]s if they
+/// were configured as [`Stdio::Piped`] in the [`ProcessOpts`].
+///
+/// [1]: http://docs.libuv.org/en/v1.x/process.html
+pub struct ProcessHandle {
+    handle: Handle<uv_process_t, Option<Callback>>,
+
+    /// The handle to the child's standard input, if it was piped.
+    pub stdin: Option<PipeHandle>,
+
+    /// The handle to the child's standard output, if it was piped.
+    pub stdout: Option<PipeHandle>,
+
+    /// The handle to the child's standard error, if it was piped.
+    pub stderr: Option<PipeHandle>,
+}
+
+impl ProcessHandle {
+    /// Spawns a new child process executing `program` with the given options.
+    /// The `on_exit` callback set in the options is executed on the main
+    /// thread once the child exits.
+    ///
+    /// The returned handle should be [`close`](ProcessHandle::close)d once
+    /// the child has exited.
+    pub fn spawn<P>(program: P, opts: ProcessOpts) -> Result<Self, Error>
+    where
+        P: AsRef<OsStr>,
+    {
+        let program = to_cstring(program.as_ref())?;
+
+        let mut args = vec![program.clone()];
+        for arg in &opts.args {
+            args.push(to_cstring(arg)?);
+        }
+        let mut args_ptrs = to_ptrs(&args);
+
+        let env = opts.env()?;
+        let mut env_ptrs = env.as_ref().map(|env| to_ptrs(env));
+
+        let cwd = opts
+            .cwd
+            .as_ref()
+            .map(|cwd| to_cstring(cwd.as_os_str()))
+            .transpose()?;
+
+        // All the pipes are created before checking for errors so that the
+        // ones that were created can be closed if another one failed.
+        let (mut stdin, mut stdout, mut stderr) = match (
+            opts.stdin.pipe(),
+            opts.stdout.pipe(),
+            opts.stderr.pipe(),
+        ) {
+            (Ok(stdin), Ok(stdout), Ok(stderr)) => (stdin, stdout, stderr),
+
+            (stdin, stdout, stderr) => {
+                let mut error = None;
+
+                for pipe in [stdin, stdout, stderr] {
+                    match pipe {
+                        Ok(pipe) => close_pipes([pipe]),
+                        Err(err) => {
+                            error.get_or_insert(err);
+                        },
+                    }
+                }
+
+                return Err(error.expect("one of the pipes failed"));
+            },
+        };
+
+        let mut stdio = [
+            opts.stdin.container(
+                0,
+                &mut stdin,
+                ffi::uv_stdio_flags_UV_READABLE_PIPE,
+            ),
+            opts.stdout.container(
+                1,
+                &mut stdout,
+                ffi::uv_stdio_flags_UV_WRITABLE_PIPE,
+            ),
+            opts.stderr.container(
+                2,
+                &mut stderr,
+                ffi::uv_stdio_flags_UV_WRITABLE_PIPE,
+            ),
+        ];
+
+        let mut options: ffi::uv_process_options_t =
+            unsafe { std::mem::zeroed() };
+
+        options.exit_cb = Some(exit_cb as _);
+        options.file = program.as_ptr();
+        options.args = args_ptrs.as_mut_ptr();
+        options.env = env_ptrs
+            .as_mut()
+            .map(|ptrs| ptrs.as_mut_ptr())
+            .unwrap_or(std::ptr::null_mut());
+        options.cwd =
+            cwd.as_ref().map(|cwd| cwd.as_ptr()).unwrap_or(std::ptr::null());
+        options.stdio_count = stdio.len() as c_int;
+        options.stdio = stdio.as_mut_ptr();
+
+        if opts.detached {
+            options.flags |= ffi::uv_process_flags_UV_PROCESS_DETACHED;
+        }
+
+        let mut handle = match Handle::<_, Option<Callback>>::alloc() {
+            Ok(handle) => handle,
+            Err(err) => {
+                close_pipes([stdin, stdout, stderr]);
+                return Err(err);
+            },
+        };
+
+        unsafe { handle.set_data(Some(opts.on_exit)) };
+
+        let retv = unsafe {
+            crate::with_loop(|uv_loop| {
+                ffi::uv_spawn(uv_loop, handle.as_mut_ptr(), &options)
+            })
+        };
+
+        if retv < 0 {
+            // Even if the spawn failed the handle has been initialized, so it
+            // has to be closed instead of simply being freed.
+            unsafe { handle.close() };
+            close_pipes([stdin, stdout, stderr]);
+
+            return Err(Error::ProcessSpawn(LibuvError::new(retv)));
+        }
+
+        Ok(Self { handle, stdin, stdout, stderr })
+    }
+
+    /// Returns the PID of the child process.
+    pub fn pid(&self) -> i32 {
+        unsafe { ffi::uv_process_get_pid(self.handle.as_ptr()) as i32 }
+    }
+
+    /// Sends the signal `signum` to the child process.
+    pub fn kill(&mut self, signum: i32) -> Result<(), Error> {
+        let retv = unsafe {
+            ffi::uv_process_kill(self.handle.as_mut_ptr(), signum as c_int)
+        };
+
+        if retv < 0 {
+            return Err(Error::ProcessKill(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the process handle together with the pipes connected to the
+    /// child's standard streams. This doesn't kill the child, use
+    /// [`kill`](ProcessHandle::kill) for that.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        for pipe in
+            [self.stdin, self.stdout, self.stderr].into_iter().flatten()
+        {
+            pipe.close();
+        }
+
+        self.handle.close()
+    }
+
+    /// Returns `true` if the child process is still running.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the process handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+/// Describes what to do with a standard stream of a child process.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Stdio {
+    /// The stream is ignored, i.e. it's redirected to `/dev/null`.
+    #[default]
+    Null,
+
+    /// The stream is inherited from the parent process.
+    Inherit,
+
+    /// The stream is connected to the parent via a [`PipeHandle`].
+    Piped,
+}
+
+impl Stdio {
+    fn pipe(&self) -> Result<Option<PipeHandle>, Error> {
+        match self {
+            Self::Piped => PipeHandle::init().map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn container(
+        &self,
+        fd: c_int,
+        pipe: &mut Option<PipeHandle>,
+        flags: ffi::uv_stdio_flags,
+    ) -> uv_stdio_container_t {
+        let mut container: uv_stdio_container_t =
+            unsafe { std::mem::zeroed() };
+
+        match (self, pipe) {
+            (Self::Piped, Some(pipe)) => {
+                container.flags = ffi::uv_stdio_flags_UV_CREATE_PIPE | flags;
+                container.data.stream = pipe.as_stream_ptr();
+            },
+
+            (Self::Inherit, _) => {
+                container.flags = ffi::uv_stdio_flags_UV_INHERIT_FD;
+                container.data.fd = fd;
+            },
+
+            _ => container.flags = ffi::uv_stdio_flags_UV_IGNORE,
+        }
+
+        container
+    }
+}
+
+/// Options passed to [`ProcessHandle::spawn`].
+pub struct ProcessOpts {
+    args: Vec<OsString>,
+    cwd: Option<PathBuf>,
+    env: HashMap<OsString, OsString>,
+    env_clear: bool,
+    detached: bool,
+    stdin: Stdio,
+    stdout: Stdio,
+    stderr: Stdio,
+    on_exit: Callback,
+}
+
+impl Default for ProcessOpts {
+    fn default() -> Self {
+        Self {
+            args: Vec::new(),
+            cwd: None,
+            env: HashMap::new(),
+            env_clear: false,
+            detached: false,
+            stdin: Stdio::default(),
+            stdout: Stdio::default(),
+            stderr: Stdio::default(),
+            on_exit: Box::new(|_, _| Ok(())),
+        }
+    }
+}
+
+impl ProcessOpts {
+    #[inline(always)]
+    pub fn builder() -> ProcessOptsBuilder {
+        ProcessOptsBuilder::default()
+    }
+
+    /// Returns the environment of the child process, or `None` if it should
+    /// simply inherit the one of the parent.
+    fn env(&self) -> Result<Option<Vec<CString>>, Error> {
+        if self.env.is_empty() && !self.env_clear {
+            return Ok(None);
+        }
+
+        let mut env = HashMap::new();
+
+        if !self.env_clear {
+            env.extend(std::env::vars_os());
+        }
+
+        env.extend(self.env.clone());
+
+        env.into_iter()
+            .map(|(key, value)| {
+                let mut var = key;
+                var.push("=");
+                var.push(value);
+                to_cstring(&var)
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+}
+
+#[derive(Default)]
+pub struct ProcessOptsBuilder(ProcessOpts);
+
+impl ProcessOptsBuilder {
+    /// Arguments passed to the program.
+    pub fn args<I, A>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<OsStr>,
+    {
+        self.0.args =
+            args.into_iter().map(|a| a.as_ref().to_owned()).collect();
+        self
+    }
+
+    /// The working directory of the child process. Defaults to the one of the
+    /// parent.
+    pub fn cwd<P: AsRef<Path>>(&mut self, cwd: P) -> &mut Self {
+        self.0.cwd = Some(cwd.as_ref().to_owned());
+        self
+    }
+
+    /// Sets an environment variable for the child process.
+    pub fn env<K, V>(&mut self, key: K, value: V) -> &mut Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.0.env.insert(key.as_ref().to_owned(), value.as_ref().to_owned());
+        self
+    }
+
+    /// Don't inherit the environment of the parent. Only the variables set via
+    /// [`env`](ProcessOptsBuilder::env) will be available to the child.
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.0.env_clear = true;
+        self
+    }
+
+    /// Spawn the child in its own process group, so that it can keep running
+    /// after Neovim exits.
+    pub fn detached(&mut self, detached: bool) -> &mut Self {
+        self.0.detached = detached;
+        self
+    }
+
+    pub fn stdin(&mut self, stdin: Stdio) -> &mut Self {
+        self.0.stdin = stdin;
+        self
+    }
+
+    pub fn stdout(&mut self, stdout: Stdio) -> &mut Self {
+        self.0.stdout = stdout;
+        self
+    }
+
+    pub fn stderr(&mut self, stderr: Stdio) -> &mut Self {
+        self.0.stderr = stderr;
+        self
+    }
+
+    /// Callback executed when the child exits. It's passed the exit status
+    /// and the number of the signal that caused the child to terminate, if
+    /// any.
+    pub fn on_exit<Cb, E>(&mut self, on_exit: Cb) -> &mut Self
+    where
+        Cb: FnOnce(i64, i32) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        self.0.on_exit = Box::new(move |exit_status, term_signal| {
+            // Type erase the callback by boxing its error.
+            on_exit(exit_status, term_signal)
+                .map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+        self
+    }
+
+    pub fn build(&mut self) -> ProcessOpts {
+        std::mem::take(&mut self.0)
+    }
+}
+
+/// Returns the null-terminated array of pointers expected by `uv_spawn`.
+fn to_ptrs(strings: &[CString]) -> Vec<*mut c_char> {
+    strings
+        .iter()
+        .map(|s| s.as_ptr() as *mut c_char)
+        .chain(std::iter::once(std::ptr::null_mut()))
+        .collect()
+}
+
+/// Closes the pipes created for a child that couldn't be spawned.
+fn close_pipes<const N: usize>(pipes: [Option<PipeHandle>; N]) {
+    for pipe in pipes.into_iter().flatten() {
+        unsafe { pipe.close() };
+    }
+}
+
+extern "C" fn exit_cb(
+    ptr: *mut uv_process_t,
+    exit_status: i64,
+    term_signal: c_int,
+) {
+    let handle: Handle<_, Option<Callback>> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if callback.is_null() {
+        return;
+    }
+
+    // The exit callback is only ever called once.
+    if let Some(callback) = unsafe { (*callback).take() } {
+        error_handler::call(HandleKind::Process, || {
+            callback(exit_status, term_signal)
+        });
+    }
+}
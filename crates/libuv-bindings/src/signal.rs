@@ -0,0 +1,150 @@
+use std::error::Error as StdError;
+use std::ffi::c_int;
+
+use libuv_sys2::{self as ffi, uv_signal_t};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, Handle, LibuvError};
+
+pub(crate) type Callback = Box<
+    dyn FnMut(&mut SignalHandle, i32) -> Result<(), Box<dyn StdError>>
+        + 'static,
+>;
+
+type StartFn =
+    unsafe extern "C" fn(*mut uv_signal_t, ffi::uv_signal_cb, c_int) -> c_int;
+
+/// Binding to libuv's [Signal handle][1] used to handle Unix signals on the
+/// Neovim event loop, without replacing the process-wide signal handlers
+/// installed by Neovim.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/signal.html
+pub struct SignalHandle {
+    handle: Handle<uv_signal_t, Callback>,
+}
+
+impl SignalHandle {
+    fn new() -> Result<Self, Error> {
+        let handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_signal_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        Ok(Self { handle })
+    }
+
+    /// Executes a callback every time the process receives the signal
+    /// `signum`, e.g. `libc::SIGUSR1`. The callback is passed the number of
+    /// the signal.
+    pub fn start<Cb, E>(signum: i32, callback: Cb) -> Result<Self, Error>
+    where
+        Cb: FnMut(&mut Self, i32) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        Self::start_with(ffi::uv_signal_start, signum, callback)
+    }
+
+    /// Same as [`start`](SignalHandle::start) but accepts an `FnOnce` closure
+    /// which will only be called the first time the signal is received,
+    /// after which the handle is closed automatically. That's why no handle
+    /// is returned.
+    pub fn once<Cb, E>(signum: i32, callback: Cb) -> Result<(), Error>
+    where
+        Cb: FnOnce(i32) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut callback = Some(callback);
+
+        Self::start_with(
+            ffi::uv_signal_start_oneshot,
+            signum,
+            move |signal, signum| {
+                // Nobody else has a handle to close the signal with.
+                let _close = unsafe { signal.handle.close_on_drop() };
+
+                match callback.take() {
+                    Some(callback) => callback(signum),
+                    None => Ok(()),
+                }
+            },
+        )
+        .map(|_| ())
+    }
+
+    fn start_with<Cb, E>(
+        start: StartFn,
+        signum: i32,
+        mut callback: Cb,
+    ) -> Result<Self, Error>
+    where
+        Cb: FnMut(&mut Self, i32) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut signal = Self::new()?;
+
+        let callback: Callback = Box::new(move |signal, signum| {
+            // Type erase the callback by boxing its error.
+            callback(signal, signum)
+                .map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { signal.handle.set_data(callback) };
+
+        let retv = unsafe {
+            start(signal.handle.as_mut_ptr(), Some(signal_cb as _), signum)
+        };
+
+        if retv < 0 {
+            unsafe { signal.close() };
+            return Err(Error::SignalStart(LibuvError::new(retv)));
+        }
+
+        Ok(signal)
+    }
+
+    /// Stops listening for the signal.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_signal_stop(self.handle.as_mut_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::SignalStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Closes the handle, freeing its memory and its callback once libuv is
+    /// done with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the handle is listening for a signal.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the handle is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
+}
+
+extern "C" fn signal_cb(ptr: *mut uv_signal_t, signum: c_int) {
+    let handle: Handle<_, Callback> = unsafe { Handle::from_raw(ptr) };
+
+    let callback = unsafe { handle.get_data() };
+
+    if !callback.is_null() {
+        let mut handle = SignalHandle { handle };
+        let callback = unsafe { &mut *callback };
+
+        error_handler::call(HandleKind::Signal, || {
+            callback(&mut handle, signum)
+        });
+    }
+}
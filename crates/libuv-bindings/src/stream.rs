@@ -0,0 +1,448 @@
+use std::error::Error as StdError;
+use std::ffi::{c_char, c_int};
+
+use libuv_sys2::{
+    self as ffi,
+    uv_buf_t,
+    uv_connect_t,
+    uv_handle_t,
+    uv_stream_t,
+};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, LibuvError};
+
+type ReadCallback<S> = Box<
+    dyn FnMut(
+            &mut S,
+            Result<Option<&[u8]>, Error>,
+        ) -> Result<(), Box<dyn StdError>>
+        + 'static,
+>;
+
+type ConnectionCallback<S> = Box<
+    dyn FnMut(&mut S, Result<(), Error>) -> Result<(), Box<dyn StdError>>
+        + 'static,
+>;
+
+type ConnectCallback<S> = Box<
+    dyn FnOnce(&mut S, Result<(), Error>) -> Result<(), Box<dyn StdError>>
+        + 'static,
+>;
+
+/// The callbacks registered on a stream handle, stored in the handle's data.
+pub struct StreamData<S> {
+    on_read: Option<ReadCallback<S>>,
+    on_connection: Option<ConnectionCallback<S>>,
+}
+
+impl<S> Default for StreamData<S> {
+    fn default() -> Self {
+        Self { on_read: None, on_connection: None }
+    }
+}
+
+pub(crate) mod sealed {
+    use super::*;
+
+    /// Implemented by the handles that can be cast to a `uv_stream_t`. It
+    /// lives in a private module so that [`StreamHandle`] can't be
+    /// implemented outside of this crate.
+    pub trait Stream: Sized {
+        /// The kind of the handle, used when reporting callback errors.
+        const KIND: HandleKind;
+
+        /// Allocates and initializes a new handle.
+        fn init() -> Result<Self, Error>;
+
+        fn as_stream_ptr(&self) -> *mut uv_stream_t;
+
+        unsafe fn from_stream_ptr(ptr: *mut uv_stream_t) -> Self;
+
+        /// Returns a pointer to the callbacks registered on the handle.
+        fn data(&self) -> *mut StreamData<Self>;
+    }
+}
+
+/// Methods shared by all the handles implementing libuv's [Stream][1]
+/// interface, i.e. [`PipeHandle`](crate::PipeHandle) and
+/// [`TcpHandle`](crate::TcpHandle).
+///
+/// [1]: http://docs.libuv.org/en/v1.x/stream.html
+pub trait StreamHandle: sealed::Stream + 'static {
+    /// Starts reading data from the stream, executing the callback every time
+    /// new data is available. The callback is passed `Ok(None)` once the
+    /// other end of the stream is closed, after which no more data will be
+    /// read.
+    ///
+    /// If this is called from inside the current read callback, the new
+    /// callback replaces it once the current one returns.
+    fn read_start<Cb, E>(&mut self, mut callback: Cb) -> Result<(), Error>
+    where
+        Cb: FnMut(&mut Self, Result<Option<&[u8]>, Error>) -> Result<(), E>
+            + 'static,
+        E: StdError + 'static,
+    {
+        let callback: ReadCallback<Self> = Box::new(move |stream, data| {
+            // Type erase the callback by boxing its error.
+            callback(stream, data)
+                .map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        unsafe { (*self.data()).on_read = Some(callback) };
+
+        let retv = unsafe {
+            ffi::uv_read_start(
+                self.as_stream_ptr(),
+                Some(alloc_cb as _),
+                Some(read_cb::<Self> as _),
+            )
+        };
+
+        if retv < 0 {
+            return Err(Error::StreamReadStart(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Stops reading data from the stream.
+    fn read_stop(&mut self) -> Result<(), Error> {
+        let retv = unsafe { ffi::uv_read_stop(self.as_stream_ptr()) };
+
+        if retv < 0 {
+            return Err(Error::StreamReadStop(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Writes some data to the stream. The data is queued and written
+    /// asynchronously, so this function returns immediately. If the write
+    /// fails later on, the error is reported to the
+    /// [error handler](crate::set_error_handler) as an
+    /// [`Error::StreamWrite`].
+    fn write<D: Into<Vec<u8>>>(&mut self, data: D) -> Result<(), Error> {
+        let req = Box::into_raw(Box::new(WriteReq {
+            req: unsafe { std::mem::zeroed() },
+            data: data.into(),
+        }));
+
+        let retv = unsafe {
+            let buf = ffi::uv_buf_init(
+                (*req).data.as_mut_ptr() as *mut c_char,
+                (*req).data.len() as _,
+            );
+
+            ffi::uv_write(
+                req as *mut ffi::uv_write_t,
+                self.as_stream_ptr(),
+                &buf,
+                1,
+                Some(write_cb::<Self> as _),
+            )
+        };
+
+        if retv < 0 {
+            drop(unsafe { Box::from_raw(req) });
+            return Err(Error::StreamWrite(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Shuts down the outgoing side of the stream once all the pending writes
+    /// have been completed. If the shutdown fails later on, the error is
+    /// reported to the [error handler](crate::set_error_handler) as an
+    /// [`Error::StreamShutdown`].
+    fn shutdown(&mut self) -> Result<(), Error> {
+        let req: Box<ffi::uv_shutdown_t> =
+            Box::new(unsafe { std::mem::zeroed() });
+        let req = Box::into_raw(req);
+
+        let retv = unsafe {
+            ffi::uv_shutdown(
+                req,
+                self.as_stream_ptr(),
+                Some(shutdown_cb::<Self> as _),
+            )
+        };
+
+        if retv < 0 {
+            drop(unsafe { Box::from_raw(req) });
+            return Err(Error::StreamShutdown(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Starts listening for incoming connections, executing the callback
+    /// every time a new connection is received. The connection can then be
+    /// accepted by calling [`accept`](StreamHandle::accept) on the server
+    /// handle.
+    ///
+    /// Like with [`read_start`](StreamHandle::read_start), calling this from
+    /// inside the current connection callback replaces it once it returns.
+    fn listen<Cb, E>(
+        &mut self,
+        backlog: u32,
+        mut callback: Cb,
+    ) -> Result<(), Error>
+    where
+        Cb: FnMut(&mut Self, Result<(), Error>) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let callback: ConnectionCallback<Self> =
+            Box::new(move |stream, status| {
+                // Type erase the callback by boxing its error.
+                callback(stream, status)
+                    .map_err(|err| Box::new(err) as Box<dyn StdError>)
+            });
+
+        unsafe { (*self.data()).on_connection = Some(callback) };
+
+        let retv = unsafe {
+            ffi::uv_listen(
+                self.as_stream_ptr(),
+                backlog as c_int,
+                Some(connection_cb::<Self> as _),
+            )
+        };
+
+        if retv < 0 {
+            return Err(Error::StreamListen(LibuvError::new(retv)));
+        }
+
+        Ok(())
+    }
+
+    /// Accepts an incoming connection. This should only be called from the
+    /// callback passed to [`listen`](StreamHandle::listen).
+    fn accept(&mut self) -> Result<Self, Error> {
+        let client = Self::init()?;
+
+        let retv = unsafe {
+            ffi::uv_accept(self.as_stream_ptr(), client.as_stream_ptr())
+        };
+
+        if retv < 0 {
+            unsafe { client.close() };
+            return Err(Error::StreamAccept(LibuvError::new(retv)));
+        }
+
+        Ok(client)
+    }
+
+    /// Closes the stream, freeing its memory and the callbacks registered on
+    /// it once libuv is done with them. Pending write requests are cancelled
+    /// silently.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the stream can't be
+    /// used after calling this.
+    unsafe fn close(self);
+
+    /// Returns `true` if the stream is reading data or listening for
+    /// connections.
+    fn is_active(&self) -> bool {
+        unsafe { ffi::uv_is_active(self.as_stream_ptr() as *const _) != 0 }
+    }
+
+    /// Returns `true` if the stream is closing.
+    fn is_closing(&self) -> bool {
+        unsafe { ffi::uv_is_closing(self.as_stream_ptr() as *const _) != 0 }
+    }
+
+    /// Returns `true` if the stream is readable.
+    fn is_readable(&self) -> bool {
+        unsafe { ffi::uv_is_readable(self.as_stream_ptr()) != 0 }
+    }
+
+    /// Returns `true` if the stream is writable.
+    fn is_writable(&self) -> bool {
+        unsafe { ffi::uv_is_writable(self.as_stream_ptr()) != 0 }
+    }
+}
+
+/// A write request together with the data it's writing, which has to be kept
+/// alive until the request completes.
+#[repr(C)]
+struct WriteReq {
+    req: ffi::uv_write_t,
+    data: Vec<u8>,
+}
+
+/// A connect request together with the stream that's connecting and the
+/// callback to execute once the connection is established.
+#[repr(C)]
+pub(crate) struct ConnectReq<S> {
+    req: uv_connect_t,
+    stream: *mut uv_stream_t,
+    callback: ConnectCallback<S>,
+}
+
+impl<S: StreamHandle> ConnectReq<S> {
+    /// Allocates a new connect request, which is freed in [`connect_cb`].
+    pub(crate) fn alloc<Cb, E>(stream: &S, callback: Cb) -> *mut uv_connect_t
+    where
+        Cb: FnOnce(&mut S, Result<(), Error>) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let callback: ConnectCallback<S> = Box::new(move |stream, status| {
+            // Type erase the callback by boxing its error.
+            callback(stream, status)
+                .map_err(|err| Box::new(err) as Box<dyn StdError>)
+        });
+
+        let req = Box::new(Self {
+            req: unsafe { std::mem::zeroed() },
+            stream: stream.as_stream_ptr(),
+            callback,
+        });
+
+        Box::into_raw(req) as *mut uv_connect_t
+    }
+
+    /// Frees a request whose connection failed synchronously.
+    pub(crate) unsafe fn free(req: *mut uv_connect_t) {
+        drop(Box::from_raw(req as *mut Self));
+    }
+}
+
+extern "C" fn alloc_cb(
+    _handle: *mut uv_handle_t,
+    suggested_size: usize,
+    buf: *mut uv_buf_t,
+) {
+    let data = vec![0u8; suggested_size].into_boxed_slice();
+    let len = data.len();
+    let ptr = Box::into_raw(data) as *mut c_char;
+    unsafe { *buf = ffi::uv_buf_init(ptr, len as _) };
+}
+
+extern "C" fn read_cb<S: StreamHandle>(
+    ptr: *mut uv_stream_t,
+    nread: ffi::ssize_t,
+    buf: *const uv_buf_t,
+) {
+    // Take back ownership of the buffer allocated in `alloc_cb`.
+    let data = unsafe {
+        let base = (*buf).base as *mut u8;
+        (!base.is_null()).then(|| {
+            let len: usize = (*buf).len as _;
+            Box::from_raw(std::ptr::slice_from_raw_parts_mut(base, len))
+        })
+    };
+
+    // This is equivalent to `EAGAIN`, there's nothing to read.
+    if nread == 0 {
+        return;
+    }
+
+    let mut stream = unsafe { S::from_stream_ptr(ptr) };
+
+    // The callback is taken out of the handle's data while it's running so
+    // that calling `read_start` from inside of it can't free it under our
+    // feet.
+    let mut callback = match unsafe { (*stream.data()).on_read.take() } {
+        Some(callback) => callback,
+        None => return,
+    };
+
+    let result = match &data {
+        Some(data) if nread > 0 => Ok(Some(&data[..nread as usize])),
+
+        // The stream was closed or there was a read error. Either way no more
+        // data will be read.
+        _ => {
+            let _ = stream.read_stop();
+            let err = LibuvError::new(nread as i32);
+            if err.is_eof() {
+                Ok(None)
+            } else {
+                Err(Error::StreamRead(err))
+            }
+        },
+    };
+
+    error_handler::call(S::KIND, || callback(&mut stream, result));
+
+    // Put the callback back unless it's been replaced in the meantime.
+    let on_read = unsafe { &mut (*stream.data()).on_read };
+    if on_read.is_none() {
+        *on_read = Some(callback);
+    }
+}
+
+extern "C" fn connection_cb<S: StreamHandle>(
+    ptr: *mut uv_stream_t,
+    status: c_int,
+) {
+    let mut stream = unsafe { S::from_stream_ptr(ptr) };
+
+    // See `read_cb` for why the callback is taken out.
+    let mut callback = match unsafe { (*stream.data()).on_connection.take() } {
+        Some(callback) => callback,
+        None => return,
+    };
+
+    let result = if status < 0 {
+        Err(Error::StreamListen(LibuvError::new(status)))
+    } else {
+        Ok(())
+    };
+
+    error_handler::call(S::KIND, || callback(&mut stream, result));
+
+    let on_connection = unsafe { &mut (*stream.data()).on_connection };
+    if on_connection.is_none() {
+        *on_connection = Some(callback);
+    }
+}
+
+pub(crate) extern "C" fn connect_cb<S: StreamHandle>(
+    req: *mut uv_connect_t,
+    status: c_int,
+) {
+    let req = unsafe { Box::from_raw(req as *mut ConnectReq<S>) };
+    let ConnectReq { stream, callback, .. } = *req;
+
+    let mut stream = unsafe { S::from_stream_ptr(stream) };
+
+    let result = if status < 0 {
+        Err(Error::StreamConnect(LibuvError::new(status)))
+    } else {
+        Ok(())
+    };
+
+    error_handler::call(S::KIND, || callback(&mut stream, result));
+}
+
+extern "C" fn write_cb<S: StreamHandle>(
+    req: *mut ffi::uv_write_t,
+    status: c_int,
+) {
+    drop(unsafe { Box::from_raw(req as *mut WriteReq) });
+
+    let err = LibuvError::new(status);
+
+    // Requests are cancelled when the stream is closed, which isn't an error.
+    if status < 0 && !err.is_canceled() {
+        error_handler::report(S::KIND, Box::new(Error::StreamWrite(err)));
+    }
+}
+
+extern "C" fn shutdown_cb<S: StreamHandle>(
+    req: *mut ffi::uv_shutdown_t,
+    status: c_int,
+) {
+    drop(unsafe { Box::from_raw(req) });
+
+    let err = LibuvError::new(status);
+
+    // Requests are cancelled when the stream is closed, which isn't an error.
+    if status < 0 && !err.is_canceled() {
+        error_handler::report(S::KIND, Box::new(Error::StreamShutdown(err)));
+    }
+}
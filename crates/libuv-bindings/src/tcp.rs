@@ -0,0 +1,135 @@
+use std::error::Error as StdError;
+use std::ffi::{c_int, CString};
+use std::net::SocketAddr;
+
+use libuv_sys2::{self as ffi, sockaddr, uv_stream_t, uv_tcp_t};
+
+use crate::error_handler::HandleKind;
+use crate::stream::{connect_cb, sealed, ConnectReq, StreamData};
+use crate::{Error, Handle, LibuvError, StreamHandle};
+
+/// Binding to libuv's [TCP handle][1] used to represent both TCP streams and
+/// servers.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/tcp.html
+pub struct TcpHandle {
+    handle: Handle<uv_tcp_t, StreamData<Self>>,
+}
+
+impl TcpHandle {
+    /// Binds a new TCP handle to an address, returning a handle which can
+    /// start listening for incoming connections with
+    /// [`listen`](StreamHandle::listen).
+    pub fn bind(addr: SocketAddr) -> Result<Self, Error> {
+        let mut tcp = <Self as sealed::Stream>::init()?;
+
+        let retv = with_sockaddr(&addr, |addr| unsafe {
+            ffi::uv_tcp_bind(tcp.handle.as_mut_ptr(), addr, 0)
+        });
+
+        if retv < 0 {
+            unsafe { StreamHandle::close(tcp) };
+            return Err(Error::TcpBind(LibuvError::new(retv)));
+        }
+
+        Ok(tcp)
+    }
+
+    /// Connects to a remote address, executing the callback once the
+    /// connection is established or has failed.
+    pub fn connect<Cb, E>(
+        addr: SocketAddr,
+        callback: Cb,
+    ) -> Result<Self, Error>
+    where
+        Cb: FnOnce(&mut Self, Result<(), Error>) -> Result<(), E> + 'static,
+        E: StdError + 'static,
+    {
+        let mut tcp = <Self as sealed::Stream>::init()?;
+
+        let req = ConnectReq::alloc(&tcp, callback);
+
+        let retv = with_sockaddr(&addr, |addr| unsafe {
+            ffi::uv_tcp_connect(
+                req,
+                tcp.handle.as_mut_ptr(),
+                addr,
+                Some(connect_cb::<Self> as _),
+            )
+        });
+
+        if retv < 0 {
+            unsafe { ConnectReq::<Self>::free(req) };
+            unsafe { StreamHandle::close(tcp) };
+            return Err(Error::StreamConnect(LibuvError::new(retv)));
+        }
+
+        Ok(tcp)
+    }
+}
+
+impl sealed::Stream for TcpHandle {
+    const KIND: HandleKind = HandleKind::Tcp;
+
+    fn init() -> Result<Self, Error> {
+        let mut handle = Handle::new(|uv_loop, handle| unsafe {
+            ffi::uv_tcp_init(uv_loop, handle.as_mut_ptr())
+        })?;
+
+        unsafe { handle.set_data(StreamData::default()) };
+
+        Ok(Self { handle })
+    }
+
+    fn as_stream_ptr(&self) -> *mut uv_stream_t {
+        self.handle.as_ptr() as *mut uv_stream_t
+    }
+
+    unsafe fn from_stream_ptr(ptr: *mut uv_stream_t) -> Self {
+        Self { handle: Handle::from_raw(ptr as *mut uv_tcp_t) }
+    }
+
+    fn data(&self) -> *mut StreamData<Self> {
+        unsafe { self.handle.get_data() }
+    }
+}
+
+impl StreamHandle for TcpHandle {
+    unsafe fn close(self) {
+        self.handle.close()
+    }
+}
+
+/// Converts a [`SocketAddr`] into the `sockaddr` expected by libuv and passes
+/// it to `fun`, returning either its return value or the error code of the
+/// conversion.
+fn with_sockaddr<F>(addr: &SocketAddr, fun: F) -> c_int
+where
+    F: FnOnce(*const sockaddr) -> c_int,
+{
+    // The string representation of an IP address never contains nul bytes.
+    let ip = CString::new(addr.ip().to_string()).unwrap();
+    let port = addr.port() as c_int;
+
+    unsafe {
+        match addr {
+            SocketAddr::V4(_) => {
+                let mut addr: ffi::sockaddr_in = std::mem::zeroed();
+                let retv = ffi::uv_ip4_addr(ip.as_ptr(), port, &mut addr);
+                if retv < 0 {
+                    return retv;
+                }
+                fun(&addr as *const _ as *const sockaddr)
+            },
+
+            SocketAddr::V6(_) => {
+                let mut addr: ffi::sockaddr_in6 = std::mem::zeroed();
+                let retv = ffi::uv_ip6_addr(ip.as_ptr(), port, &mut addr);
+                if retv < 0 {
+                    return retv;
+                }
+                fun(&addr as *const _ as *const sockaddr)
+            },
+        }
+    }
+}
@@ -3,7 +3,8 @@ use std::time::Duration;
 
 use libuv_sys2::{self as ffi, uv_timer_t};
 
-use crate::{Error, Handle};
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, Handle, LibuvError};
 
 pub(crate) type Callback = Box<
     dyn FnMut(&mut TimerHandle) -> Result<(), Box<dyn StdError>> + 'static,
@@ -58,15 +59,17 @@ impl TimerHandle {
         };
 
         if retv < 0 {
-            return Err(Error::TimerStart);
+            unsafe { timer.close() };
+            return Err(Error::TimerStart(LibuvError::new(retv)));
         }
 
         Ok(timer)
     }
 
     /// Same as [`start`](TimerHandle::start) but accepts an `FnOnce` closure
-    /// which will only be called once before being automatically stopped.
-    pub fn once<Cb, E>(timeout: Duration, callback: Cb) -> Result<Self, Error>
+    /// which will only be called once, after which the timer is closed
+    /// automatically. That's why no handle is returned.
+    pub fn once<Cb, E>(timeout: Duration, callback: Cb) -> Result<(), Error>
     where
         Cb: FnOnce() -> Result<(), E> + 'static,
         E: StdError + 'static,
@@ -74,10 +77,15 @@ impl TimerHandle {
         let mut callback = Some(callback);
 
         Self::start(timeout, Duration::from_millis(0), move |timer| {
-            let res = callback.take().unwrap()();
-            timer.stop().unwrap();
-            res
+            // Nobody else has a handle to close the timer with.
+            let _close = unsafe { timer.handle.close_on_drop() };
+
+            match callback.take() {
+                Some(callback) => callback(),
+                None => Ok(()),
+            }
         })
+        .map(|_| ())
     }
 
     /// Stops the timer.
@@ -85,11 +93,32 @@ impl TimerHandle {
         let retv = unsafe { ffi::uv_timer_stop(self.handle.as_mut_ptr()) };
 
         if retv < 0 {
-            return Err(Error::TimerStop);
+            return Err(Error::TimerStop(LibuvError::new(retv)));
         }
 
         Ok(())
     }
+
+    /// Closes the timer, freeing its memory and its callback once libuv is done
+    /// with them.
+    ///
+    /// # Safety
+    ///
+    /// This has to be called from the main thread, and the handle can't be
+    /// used after calling this.
+    pub unsafe fn close(self) {
+        self.handle.close()
+    }
+
+    /// Returns `true` if the timer is active.
+    pub fn is_active(&self) -> bool {
+        self.handle.is_active()
+    }
+
+    /// Returns `true` if the timer is closing.
+    pub fn is_closing(&self) -> bool {
+        self.handle.is_closing()
+    }
 }
 
 extern "C" fn timer_cb(ptr: *mut uv_timer_t) {
@@ -101,8 +130,6 @@ extern "C" fn timer_cb(ptr: *mut uv_timer_t) {
         let mut handle = TimerHandle { handle };
         let callback = unsafe { &mut *callback };
 
-        if let Err(_err) = callback(&mut handle) {
-            // TODO: what now?
-        }
+        error_handler::call(HandleKind::Timer, || callback(&mut handle));
     }
 }
@@ -0,0 +1,38 @@
+use std::ffi::{c_char, CStr, CString, OsStr};
+use std::path::PathBuf;
+
+use crate::Error;
+
+/// Converts an [`OsStr`] into a [`CString`] which can be passed to libuv.
+#[cfg(unix)]
+pub(crate) fn to_cstring(s: &OsStr) -> Result<CString, Error> {
+    use std::os::unix::ffi::OsStrExt;
+    CString::new(s.as_bytes()).map_err(|_| Error::NulByte)
+}
+
+/// Converts an [`OsStr`] into a [`CString`] which can be passed to libuv.
+#[cfg(not(unix))]
+pub(crate) fn to_cstring(s: &OsStr) -> Result<CString, Error> {
+    CString::new(s.to_string_lossy().into_owned()).map_err(|_| Error::NulByte)
+}
+
+/// Converts a nul-terminated path returned by libuv into a [`PathBuf`],
+/// returning `None` if the pointer is null.
+pub(crate) unsafe fn to_path(ptr: *const c_char) -> Option<PathBuf> {
+    if ptr.is_null() {
+        return None;
+    }
+
+    let bytes = CStr::from_ptr(ptr).to_bytes();
+
+    #[cfg(unix)]
+    let path = {
+        use std::os::unix::ffi::OsStrExt;
+        OsStr::from_bytes(bytes).into()
+    };
+
+    #[cfg(not(unix))]
+    let path = String::from_utf8_lossy(bytes).into_owned().into();
+
+    Some(path)
+}
@@ -0,0 +1,114 @@
+use std::error::Error as StdError;
+use std::ffi::c_int;
+
+use libuv_sys2::{self as ffi, uv_work_t};
+use luajit_bindings::panic::{self, Panic};
+
+use crate::error_handler::{self, HandleKind};
+use crate::{Error, LibuvError};
+
+type Work<T> = Box<dyn FnOnce() -> T + Send + 'static>;
+
+type After<T> = Box<dyn FnOnce(T) -> Result<(), Box<dyn StdError>> + 'static>;
+
+/// A work request together with the closures to execute and the output of
+/// the work, which is written by a thread of the pool and read back on the
+/// main thread.
+#[repr(C)]
+struct WorkReq<T> {
+    req: uv_work_t,
+    work: Option<Work<T>>,
+    output: Option<Result<T, Panic>>,
+    after: After<T>,
+}
+
+/// Executes `work` on libuv's [thread pool][1], then calls `after` with its
+/// output on the main thread. Unlike `work`, the `after` callback can safely
+/// call functions from the Neovim API.
+///
+/// If `work` panics the panic is caught and reported as an error instead of
+/// calling `after`.
+///
+/// [1]: http://docs.libuv.org/en/v1.x/threadpool.html
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::{api, libuv};
+///
+/// libuv::queue_work(
+///     || (1..=100_000u64).sum::<u64>(),
+///     |sum| {
+///         api::out_write(format!("The sum is {sum}\n"));
+///         Ok::<_, std::convert::Infallible>(())
+///     },
+/// )?;
+/// ```
+pub fn queue_work<W, A, T, E>(work: W, after: A) -> Result<(), Error>
+where
+    W: FnOnce() -> T + Send + 'static,
+    A: FnOnce(T) -> Result<(), E> + 'static,
+    T: Send + 'static,
+    E: StdError + 'static,
+{
+    let after: After<T> = Box::new(move |output| {
+        // Type erase the callback by boxing its error.
+        after(output).map_err(|err| Box::new(err) as Box<dyn StdError>)
+    });
+
+    let req = Box::into_raw(Box::new(WorkReq {
+        req: unsafe { std::mem::zeroed() },
+        work: Some(Box::new(work) as Work<T>),
+        output: None,
+        after,
+    }));
+
+    let retv = unsafe {
+        crate::with_loop(|uv_loop| {
+            ffi::uv_queue_work(
+                uv_loop,
+                req as *mut uv_work_t,
+                Some(work_cb::<T> as _),
+                Some(after_work_cb::<T> as _),
+            )
+        })
+    };
+
+    if retv < 0 {
+        drop(unsafe { Box::from_raw(req) });
+        return Err(Error::WorkQueue(LibuvError::new(retv)));
+    }
+
+    Ok(())
+}
+
+/// Called on a thread of the pool.
+extern "C" fn work_cb<T>(req: *mut uv_work_t) {
+    let req = unsafe { &mut *(req as *mut WorkReq<T>) };
+
+    if let Some(work) = req.work.take() {
+        // Unwinding across the FFI boundary is undefined behaviour.
+        req.output = Some(panic::catch(work));
+    }
+}
+
+/// Called on the main thread once `work_cb` has returned.
+extern "C" fn after_work_cb<T>(req: *mut uv_work_t, _status: c_int) {
+    let req = unsafe { Box::from_raw(req as *mut WorkReq<T>) };
+    let WorkReq { output, after, .. } = *req;
+
+    error_handler::call(HandleKind::Work, || match output {
+        Some(Ok(output)) => after(output),
+
+        Some(Err(panic)) => {
+            let msg = match panic.location {
+                Some(location) => format!("{} at {location}", panic.message),
+                None => panic.message,
+            };
+            Err(Box::new(Error::WorkPanic(msg)) as _)
+        },
+
+        // The work was never executed.
+        None => Ok(()),
+    });
+}
@@ -18,8 +18,24 @@ pub enum Error {
     )]
     PushError { ty: &'static str, message: Option<String> },
 
-    #[error("Lua runtime error: {0}")]
-    RuntimeError(String),
+    #[error(
+        "Lua runtime error: {message}{}",
+        traceback
+            .as_ref()
+            .map(|tb| format!("\nstack traceback:\n{tb}"))
+            .unwrap_or_default()
+    )]
+    RuntimeError {
+        /// The error message.
+        message: String,
+
+        /// The stack traceback at the point where the error was raised, one
+        /// frame per line.
+        traceback: Option<String>,
+    },
+
+    #[error("Userdata of type {ty} couldn't be borrowed: {message}.")]
+    BorrowError { ty: &'static str, message: String },
 
     #[error("Lua memory error: {0}")]
     MemoryError(String),
@@ -24,8 +24,12 @@ pub const fn lua_upvalueindex(i: c_int) -> c_int {
     LUA_GLOBALSINDEX - i
 }
 
+// Option for multiple returns in `lua_pcall` and `lua_call`.
+pub const LUA_MULTRET: c_int = -1;
+
 // Thread status.
 pub const LUA_OK: c_int = 0;
+pub const LUA_YIELD: c_int = 1;
 pub const LUA_ERRRUN: c_int = 2;
 pub const LUA_ERRMEM: c_int = 4;
 pub const LUA_ERRERR: c_int = 5;
@@ -42,6 +46,26 @@ pub const LUA_TFUNCTION: c_int = 6;
 pub const LUA_TUSERDATA: c_int = 7;
 pub const LUA_TTHREAD: c_int = 8;
 
+// https://www.lua.org/manual/5.1/manual.html#lua_Debug
+#[repr(C)]
+#[doc(hidden)]
+pub struct lua_Debug {
+    pub event: c_int,
+    pub name: *const c_char,
+    pub namewhat: *const c_char,
+    pub what: *const c_char,
+    pub source: *const c_char,
+    pub currentline: c_int,
+    pub nups: c_int,
+    pub linedefined: c_int,
+    pub lastlinedefined: c_int,
+    pub short_src: [c_char; LUA_IDSIZE],
+    i_ci: c_int,
+}
+
+// Size of `lua_Debug.short_src`.
+pub const LUA_IDSIZE: usize = 60;
+
 // https://www.lua.org/manual/5.1/manual.html#lua_CFunction
 pub type lua_CFunction = unsafe extern "C" fn(L: *mut lua_State) -> c_int;
 
@@ -55,6 +79,9 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_call
     pub fn lua_call(L: *mut lua_State, nargs: c_int, nresults: c_int);
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_checkstack
+    pub fn lua_checkstack(L: *mut lua_State, sz: c_int) -> c_int;
+
     // https://www.lua.org/manual/5.1/manual.html#lua_createtable
     pub fn lua_createtable(L: *mut lua_State, narr: c_int, nrec: c_int);
 
@@ -64,12 +91,28 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_call
     pub fn lua_getfield(L: *mut lua_State, index: c_int, k: *const c_char);
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_getstack
+    pub fn lua_getstack(
+        L: *mut lua_State,
+        level: c_int,
+        ar: *mut lua_Debug,
+    ) -> c_int;
+
     // https://www.lua.org/manual/5.1/manual.html#lua_getmetatable
     pub fn lua_getmetatable(L: *mut lua_State, index: c_int) -> c_int;
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_gettable
+    pub fn lua_gettable(L: *mut lua_State, index: c_int);
+
     // https://www.lua.org/manual/5.1/manual.html#lua_gettop
     pub fn lua_gettop(L: *mut lua_State) -> c_int;
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_insert
+    pub fn lua_insert(L: *mut lua_State, index: c_int);
+
+    // https://www.lua.org/manual/5.1/manual.html#lua_newthread
+    pub fn lua_newthread(L: *mut lua_State) -> *mut lua_State;
+
     // https://www.lua.org/manual/5.1/manual.html#lua_newuserdata
     pub fn lua_newuserdata(L: *mut lua_State, size: usize) -> *mut c_void;
 
@@ -114,6 +157,12 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_pushvalue
     pub fn lua_pushvalue(L: *mut lua_State, index: c_int);
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_rawequal
+    pub fn lua_rawequal(L: *mut lua_State, idx1: c_int, idx2: c_int) -> c_int;
+
+    // https://www.lua.org/manual/5.1/manual.html#lua_rawget
+    pub fn lua_rawget(L: *mut lua_State, index: c_int);
+
     // https://www.lua.org/manual/5.1/manual.html#lua_rawgeti
     pub fn lua_rawgeti(L: *mut lua_State, index: c_int, n: c_int);
 
@@ -123,9 +172,21 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_rawseti
     pub fn lua_rawseti(L: *mut lua_State, index: c_int, n: c_int);
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_remove
+    pub fn lua_remove(L: *mut lua_State, index: c_int);
+
+    // https://www.lua.org/manual/5.1/manual.html#lua_resume
+    pub fn lua_resume(L: *mut lua_State, narg: c_int) -> c_int;
+
+    // https://www.lua.org/manual/5.1/manual.html#lua_setmetatable
+    pub fn lua_setmetatable(L: *mut lua_State, index: c_int) -> c_int;
+
     // https://www.lua.org/manual/5.1/manual.html#lua_settop
     pub fn lua_settop(L: *mut lua_State, index: c_int);
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_status
+    pub fn lua_status(L: *mut lua_State) -> c_int;
+
     // https://www.lua.org/manual/5.1/manual.html#lua_toboolean
     pub fn lua_toboolean(L: *mut lua_State, index: c_int) -> c_int;
 
@@ -142,6 +203,12 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_tonumber
     pub fn lua_tonumber(L: *mut lua_State, index: c_int) -> lua_Number;
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_topointer
+    pub fn lua_topointer(L: *mut lua_State, index: c_int) -> *const c_void;
+
+    // https://www.lua.org/manual/5.1/manual.html#lua_tothread
+    pub fn lua_tothread(L: *mut lua_State, index: c_int) -> *mut lua_State;
+
     // https://www.lua.org/manual/5.1/manual.html#lua_touserdata
     pub fn lua_touserdata(L: *mut lua_State, index: c_int) -> *mut c_void;
 
@@ -151,6 +218,12 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#lua_typename
     pub fn lua_typename(L: *mut lua_State, tp: c_int) -> *const c_char;
 
+    // https://www.lua.org/manual/5.1/manual.html#lua_xmove
+    pub fn lua_xmove(from: *mut lua_State, to: *mut lua_State, n: c_int);
+
+    // https://www.lua.org/manual/5.1/manual.html#lua_yield
+    pub fn lua_yield(L: *mut lua_State, nresults: c_int) -> c_int;
+
     // Lua auxiliary library.
 
     // https://www.lua.org/manual/5.1/manual.html#luaL_error
@@ -159,6 +232,14 @@ extern "C" {
     // https://www.lua.org/manual/5.1/manual.html#luaL_ref
     pub fn luaL_ref(L: *mut lua_State, t: c_int) -> c_int;
 
+    // https://www.lua.org/manual/5.2/manual.html#luaL_traceback
+    pub fn luaL_traceback(
+        L: *mut lua_State,
+        L1: *mut lua_State,
+        msg: *const c_char,
+        level: c_int,
+    );
+
     // https://www.lua.org/manual/5.1/manual.html#luaL_unref
     pub fn luaL_unref(L: *mut lua_State, t: c_int, r#ref: c_int);
 }
@@ -1,11 +1,19 @@
 use std::error::Error;
-use std::ffi::{c_int, CStr};
+use std::ffi::c_int;
+use std::fmt;
 use std::mem;
 use std::ptr;
 
 use crate::ffi::{self, lua_State};
+use crate::macros::cstr;
+use crate::registry::{registry_ref_conversions, RegistryRef};
 use crate::{utils, Poppable, Pushable};
 
+/// The type-erased Rust closure called by the C functions created via
+/// [`push_callback`].
+pub(crate) type Callback =
+    Box<dyn Fn(*mut lua_State) -> Result<c_int, crate::Error> + 'static>;
+
 /// Stores a function in the Lua registry, returning its ref.
 pub fn store<F, A, R, E>(fun: F) -> c_int
 where
@@ -14,37 +22,55 @@ where
     R: Pushable,
     E: Error + 'static,
 {
-    type Callback =
-        Box<dyn Fn(*mut lua_State) -> Result<c_int, crate::Error> + 'static>;
-
-    unsafe extern "C" fn c_fun(lstate: *mut lua_State) -> c_int {
-        let fun = {
-            let idx = ffi::lua_upvalueindex(1);
-            let upv = ffi::lua_touserdata(lstate, idx) as *mut Callback;
-            &**upv
-        };
-
-        fun(lstate).unwrap_or_else(|err| utils::handle_error(lstate, &err))
-    }
-
     unsafe {
         crate::with_state(move |lstate| {
             let fun = move |lstate| {
-                let args = A::pop(lstate)?;
+                let args = A::pop_many(lstate, ffi::lua_gettop(lstate))?;
                 let ret = fun(args)
                     .map_err(crate::Error::push_error_from_err::<R, _>)?;
                 ret.push(lstate)
             };
 
-            let ud = ffi::lua_newuserdata(lstate, mem::size_of::<Callback>());
-            ptr::write(ud as *mut Callback, Box::new(fun));
-
-            ffi::lua_pushcclosure(lstate, c_fun, 1);
+            push_callback(lstate, Box::new(fun));
             ffi::luaL_ref(lstate, ffi::LUA_REGISTRYINDEX)
         })
     }
 }
 
+/// Pushes a C function calling `callback` on the stack.
+pub(crate) unsafe fn push_callback(
+    lstate: *mut lua_State,
+    callback: Callback,
+) {
+    unsafe extern "C" fn c_fun(lstate: *mut lua_State) -> c_int {
+        let fun = {
+            let idx = ffi::lua_upvalueindex(1);
+            let upv = ffi::lua_touserdata(lstate, idx) as *mut Callback;
+            &**upv
+        };
+
+        // A `Yield` pushed outside of a callback shouldn't make this one
+        // yield.
+        crate::thread::take_yield();
+
+        // Unwinding into Lua's C frames is undefined behaviour, so panics
+        // are turned into Lua errors.
+        match crate::panic::catch(|| fun(lstate)) {
+            Ok(Ok(nret)) if crate::thread::take_yield() => {
+                ffi::lua_yield(lstate, nret)
+            },
+            Ok(Ok(nret)) => nret,
+            Ok(Err(err)) => utils::handle_error(lstate, &err),
+            Err(panic) => utils::raise(lstate, &panic.to_string()),
+        }
+    }
+
+    let ud = ffi::lua_newuserdata(lstate, mem::size_of::<Callback>());
+    ptr::write(ud as *mut Callback, callback);
+
+    ffi::lua_pushcclosure(lstate, c_fun, 1);
+}
+
 /// Calls a function previously stored in the Lua registry via [store].
 pub fn call<A, R>(lua_ref: c_int, args: A) -> Result<R, crate::Error>
 where
@@ -53,36 +79,202 @@ where
 {
     unsafe {
         crate::with_state(move |lstate| {
+            let errfunc = push_traceback(lstate);
             ffi::lua_rawgeti(lstate, ffi::LUA_REGISTRYINDEX, lua_ref);
-            let nargs = args.push(lstate)?;
+            pcall(lstate, errfunc, args)
+        })
+    }
+}
 
-            match ffi::lua_pcall(lstate, nargs, -1, 0 /* <- errorfunc */) {
-                ffi::LUA_OK => R::pop(lstate),
+/// Calls the Lua function found at the dotted `path`, e.g.
+/// `"vim.lsp.buf.format"`, starting from the global environment.
+///
+/// Each segment of the path is resolved using normal indexing, so lazily
+/// loaded modules like `vim.lsp` work as expected. Errors raised while
+/// resolving the path or while executing the function are returned as
+/// [`Error::RuntimeError`](crate::Error::RuntimeError)s, together with a
+/// traceback.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua;
+///
+/// let cwd = lua::call_path::<_, String>("vim.fn.getcwd", ())?;
+/// ```
+pub fn call_path<A, R>(path: &str, args: A) -> Result<R, crate::Error>
+where
+    A: Pushable,
+    R: Poppable,
+{
+    unsafe {
+        crate::with_state(move |lstate| {
+            let errfunc = push_traceback(lstate);
 
-                err_code => {
-                    let msg = CStr::from_ptr(ffi::lua_tostring(lstate, -1))
-                        .to_string_lossy()
-                        .to_string();
+            // Resolve the path in protected mode, leaving the value it points
+            // to on the stack.
+            ffi::lua_pushcfunction(lstate, resolve_path);
+            push_str(lstate, path.as_bytes());
 
-                    ffi::lua_pop(lstate, 1);
+            let status = ffi::lua_pcall(lstate, 1, 1, errfunc);
+            check_status(lstate, errfunc, status)?;
 
-                    match err_code {
-                        ffi::LUA_ERRRUN => {
-                            Err(crate::Error::RuntimeError(msg))
-                        },
+            pcall(lstate, errfunc, args)
+        })
+    }
+}
 
-                        ffi::LUA_ERRMEM => Err(crate::Error::MemoryError(msg)),
+/// Binding to Lua's `require`, loading the module called `name` and returning
+/// its value.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua::{self, LuaTable};
+///
+/// let telescope = lua::require::<LuaTable>("telescope")?;
+/// ```
+pub fn require<R>(name: &str) -> Result<R, crate::Error>
+where
+    R: Poppable,
+{
+    call_path("require", name)
+}
 
-                        ffi::LUA_ERRERR => {
-                            unreachable!("errorfunc is 0, this never happens!")
-                        },
+/// Pushes `debug.traceback` on the stack to be used as the message handler
+/// of `lua_pcall`, returning its absolute index.
+unsafe fn push_traceback(lstate: *mut lua_State) -> c_int {
+    ffi::lua_getglobal(lstate, cstr!("debug"));
+    ffi::lua_getfield(lstate, -1, cstr!("traceback"));
+    ffi::lua_remove(lstate, -2);
+    ffi::lua_gettop(lstate)
+}
 
-                        _ => unreachable!(),
-                    }
-                },
-            }
-        })
+/// Calls the function at the top of the stack with `args` and pops its
+/// return values, also removing the message handler at `errfunc` and
+/// everything above it.
+unsafe fn pcall<A, R>(
+    lstate: *mut lua_State,
+    errfunc: c_int,
+    args: A,
+) -> Result<R, crate::Error>
+where
+    A: Pushable,
+    R: Poppable,
+{
+    let nargs = match args.push(lstate) {
+        Ok(nargs) => nargs,
+
+        Err(err) => {
+            ffi::lua_settop(lstate, errfunc - 1);
+            return Err(err);
+        },
+    };
+
+    let status = ffi::lua_pcall(lstate, nargs, ffi::LUA_MULTRET, errfunc);
+
+    check_status(lstate, errfunc, status)?;
+
+    let ret = R::pop_many(lstate, ffi::lua_gettop(lstate) - errfunc);
+
+    ffi::lua_settop(lstate, errfunc - 1);
+
+    ret
+}
+
+/// The line separating the error message from the traceback in the strings
+/// returned by `debug.traceback`.
+const TRACEBACK_HEADER: &str = "\nstack traceback:\n";
+
+/// Turns the status code returned by `lua_pcall` into a `Result`, cleaning
+/// up the stack if the call failed.
+unsafe fn check_status(
+    lstate: *mut lua_State,
+    errfunc: c_int,
+    status: c_int,
+) -> Result<(), crate::Error> {
+    if status == ffi::LUA_OK {
+        return Ok(());
+    }
+
+    let msg = error_message(lstate);
+
+    ffi::lua_settop(lstate, errfunc - 1);
+
+    if status == ffi::LUA_ERRMEM {
+        return Err(crate::Error::MemoryError(msg));
+    }
+
+    Err(runtime_error(msg))
+}
+
+/// Returns the error message at the top of the stack, without popping it.
+pub(crate) unsafe fn error_message(lstate: *mut lua_State) -> String {
+    if ffi::lua_type(lstate, -1) == ffi::LUA_TSTRING {
+        let mut len = 0;
+        let ptr = ffi::lua_tolstring(lstate, -1, &mut len);
+        let bytes = std::slice::from_raw_parts(ptr as *const u8, len);
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        format!("(error object is a {} value)", utils::debug_type(lstate, -1))
+    }
+}
+
+/// Creates a runtime error from an error message which may be followed by a
+/// traceback, like the ones returned by `debug.traceback`.
+pub(crate) fn runtime_error(msg: String) -> crate::Error {
+    let (message, traceback) = match msg.split_once(TRACEBACK_HEADER) {
+        Some((message, traceback)) => {
+            (message.to_owned(), Some(traceback.to_owned()))
+        },
+        None => (msg, None),
+    };
+
+    crate::Error::RuntimeError { message, traceback }
+}
+
+#[inline]
+unsafe fn push_str<S: AsRef<[u8]> + ?Sized>(lstate: *mut lua_State, s: &S) {
+    let bytes = s.as_ref();
+    ffi::lua_pushlstring(lstate, bytes.as_ptr() as *const _, bytes.len());
+}
+
+/// Resolves the dotted path passed as its only argument, returning the value
+/// it points to.
+unsafe extern "C" fn resolve_path(lstate: *mut lua_State) -> c_int {
+    let path = {
+        let mut len = 0;
+        let ptr = ffi::lua_tolstring(lstate, 1, &mut len);
+        std::slice::from_raw_parts(ptr as *const u8, len)
+    };
+
+    ffi::lua_pushvalue(lstate, ffi::LUA_GLOBALSINDEX);
+
+    // The length of the part of the path that's been resolved so far.
+    let mut resolved = 0;
+
+    for segment in path.split(|&b| b == b'.') {
+        // Indexing `nil` would raise an error anyway, but this way the
+        // message says which part of the path is missing.
+        if ffi::lua_type(lstate, -1) == ffi::LUA_TNIL {
+            push_str(
+                lstate,
+                &format!(
+                    "'{}' is nil",
+                    String::from_utf8_lossy(&path[..resolved])
+                ),
+            );
+            ffi::lua_error(lstate);
+        }
+
+        push_str(lstate, segment);
+        ffi::lua_gettable(lstate, -2);
+        ffi::lua_remove(lstate, -2);
+
+        resolved += segment.len() + (resolved != 0) as usize;
     }
+
+    1
 }
 
 /// Removes the function reference stored in the Lua registry
@@ -93,3 +285,48 @@ pub fn remove(lua_ref: c_int) {
         })
     }
 }
+
+/// A reference to a Lua function stored in the registry, which keeps the
+/// function alive for as long as it exists.
+#[derive(Clone, PartialEq)]
+pub struct LuaFunction {
+    inner: RegistryRef,
+}
+
+impl fmt::Debug for LuaFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "function: {:p}", self.inner.as_ptr())
+    }
+}
+
+registry_ref_conversions!(LuaFunction, ffi::LUA_TFUNCTION);
+
+impl LuaFunction {
+    pub(crate) fn from_ref(inner: RegistryRef) -> Self {
+        Self { inner }
+    }
+
+    pub(crate) fn as_ref(&self) -> &RegistryRef {
+        &self.inner
+    }
+
+    /// Creates a new Lua function from a Rust closure.
+    pub fn from_fn<F, A, R, E>(fun: F) -> Self
+    where
+        F: Fn(A) -> Result<R, E> + 'static,
+        A: Poppable,
+        R: Pushable,
+        E: Error + 'static,
+    {
+        Self::from_ref(RegistryRef::from_raw(store(fun)))
+    }
+
+    /// Calls the function with the given arguments.
+    pub fn call<A, R>(&self, args: A) -> Result<R, crate::Error>
+    where
+        A: Pushable,
+        R: Poppable,
+    {
+        call(self.inner.lua_ref(), args)
+    }
+}
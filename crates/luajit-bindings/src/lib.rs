@@ -3,14 +3,35 @@ mod error;
 pub mod ffi;
 pub mod function;
 pub mod macros;
+pub mod panic;
 mod poppable;
 mod pushable;
+mod registry;
+#[cfg(feature = "serde")]
+pub mod serde;
 mod state;
+mod table;
+mod thread;
+mod userdata;
 pub mod utils;
+mod value;
+mod variadic;
 
 pub use error::Error;
+pub use function::{call_path, require, LuaFunction};
 #[doc(hidden)]
 pub use macros::__print;
 pub use poppable::Poppable;
 pub use pushable::Pushable;
 pub use state::{init, with_state};
+pub use table::{Ipairs, LuaTable, Pairs};
+pub use thread::{LuaThread, ThreadStatus, Yield};
+pub use userdata::{
+    LuaUserData,
+    MetaMethod,
+    UserData,
+    UserDataMethods,
+    UserDataRef,
+};
+pub use value::LuaValue;
+pub use variadic::{MultiValue, Variadic};
@@ -1,7 +1,3 @@
-use std::ffi::c_char;
-
-use crate::ffi::*;
-
 // Taken from https://github.com/khvzak/mlua/blob/master/src/macros.rs#L11
 #[macro_export]
 macro_rules! cstr {
@@ -66,16 +62,6 @@ macro_rules! print {
 /// Prints a message to the Neovim message area.
 #[doc(hidden)]
 pub fn __print(text: impl Into<String>) {
-    unsafe {
-        crate::with_state(move |lstate| {
-            let text = text.into();
-            lua_getglobal(lstate, cstr!("print"));
-            lua_pushlstring(
-                lstate,
-                text.as_ptr() as *const c_char,
-                text.len(),
-            );
-            lua_call(lstate, 1, 0);
-        })
-    };
+    // There's nowhere to report the error to if `print` itself fails.
+    let _ = crate::call_path::<_, ()>("print", text.into());
 }
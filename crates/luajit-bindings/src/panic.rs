@@ -0,0 +1,105 @@
+//! Utilities to stop Rust panics from unwinding into C code.
+//!
+//! Unwinding across an `extern "C"` function is undefined behaviour, so every
+//! function called by Lua or by libuv has to catch panics before returning.
+//! By default caught panics are turned into errors, but if the
+//! `abort-on-panic` feature is enabled the process is aborted instead.
+
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::error::Error as StdError;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+thread_local! {
+    /// How many calls to [`catch`] are currently on the stack.
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+
+    /// The location of the last panic caught by [`catch`].
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// A panic caught by [`catch`].
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Panic {
+    /// The message the code panicked with.
+    pub message: String,
+
+    /// The source location of the panic, e.g. `src/lib.rs:42:5`.
+    pub location: Option<String>,
+}
+
+impl fmt::Display for Panic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "panicked at '{}'", self.message)?;
+
+        if let Some(location) = &self.location {
+            write!(f, ", {location}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl StdError for Panic {}
+
+/// Calls `fun`, catching any panic.
+///
+/// If the `abort-on-panic` feature is enabled the process is aborted
+/// instead, after the panic has been reported by the panic hook.
+pub fn catch<F, R>(fun: F) -> Result<R, Panic>
+where
+    F: FnOnce() -> R,
+{
+    install_hook();
+
+    CATCHING.with(|c| c.set(c.get() + 1));
+    let res = panic::catch_unwind(AssertUnwindSafe(fun));
+    CATCHING.with(|c| c.set(c.get() - 1));
+
+    res.map_err(|payload| {
+        if cfg!(feature = "abort-on-panic") {
+            std::process::abort();
+        }
+
+        Panic {
+            message: payload_msg(&*payload).to_owned(),
+            location: LOCATION.with(|l| l.borrow_mut().take()),
+        }
+    })
+}
+
+/// Installs a panic hook which records the location of the panics happening
+/// inside [`catch`] instead of printing them to stderr, which would mess up
+/// Neovim's UI. Panics happening anywhere else are forwarded to the previous
+/// hook, and so are all panics if the process is going to be aborted.
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let prev = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if cfg!(feature = "abort-on-panic")
+                || CATCHING.with(Cell::get) == 0
+            {
+                return prev(info);
+            }
+
+            let location = info.location().map(ToString::to_string);
+            LOCATION.with(|l| *l.borrow_mut() = location);
+        }));
+    });
+}
+
+/// Extracts the message from the payload of a panic.
+fn payload_msg(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg
+    } else {
+        "Box<dyn Any>"
+    }
+}
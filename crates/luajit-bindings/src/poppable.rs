@@ -1,4 +1,6 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ffi::c_int;
 use std::hash::Hash;
 
 use crate::ffi::*;
@@ -9,6 +11,25 @@ use crate::Error;
 pub trait Poppable: Sized {
     /// Pops the value at the top of the stack.
     unsafe fn pop(lua_state: *mut lua_State) -> Result<Self, Error>;
+
+    /// Pops the `n` values at the top of the stack, e.g. all the arguments
+    /// a function was called with or all the values it returned.
+    ///
+    /// The default implementation adjusts the values to a single one like Lua
+    /// does: if there are no values it's treated as `nil`, and if there's
+    /// more than one all but the first are discarded.
+    unsafe fn pop_many(
+        lua_state: *mut lua_State,
+        n: c_int,
+    ) -> Result<Self, Error> {
+        match n {
+            0 => lua_pushnil(lua_state),
+            1 => {},
+            _ => lua_pop(lua_state, n - 1),
+        }
+
+        Self::pop(lua_state)
+    }
 }
 
 impl Poppable for () {
@@ -25,6 +46,14 @@ impl Poppable for () {
             other => Err(Error::pop_wrong_type::<Self>(LUA_TNIL, other)),
         }
     }
+
+    unsafe fn pop_many(
+        state: *mut lua_State,
+        n: c_int,
+    ) -> Result<Self, Error> {
+        lua_pop(state, n);
+        Ok(())
+    }
 }
 
 impl Poppable for bool {
@@ -189,6 +218,68 @@ where
     }
 }
 
+impl Poppable for Cow<'static, str> {
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        <String as Poppable>::pop(state).map(Cow::Owned)
+    }
+}
+
+impl<T> Poppable for Box<T>
+where
+    T: Poppable,
+{
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        T::pop(state).map(Box::new)
+    }
+}
+
+impl<T, const N: usize> Poppable for [T; N]
+where
+    T: Poppable,
+{
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        <Vec<T> as Poppable>::pop(state)?.try_into().map_err(|vec: Vec<T>| {
+            Error::pop_error(
+                std::any::type_name::<Self>(),
+                format!("expected {N} elements, found {}", vec.len()),
+            )
+        })
+    }
+}
+
+/// Pops a dictionary-like table, calling `insert` with every key-value pair.
+unsafe fn pop_dict<K, V, F>(
+    state: *mut lua_State,
+    mut insert: F,
+) -> Result<(), Error>
+where
+    K: Poppable,
+    V: Poppable,
+    F: FnMut(K, V),
+{
+    // TODO: check that the table is an dictionary-like table and not an
+    // array-like one.
+
+    lua_pushnil(state);
+
+    while lua_next(state, -2) != 0 {
+        let value = V::pop(state)?;
+
+        // NOTE: the following `K::pop` will pop the key, so we push another
+        // copy of the key on the stack for the next iteration.
+        lua_pushvalue(state, -1);
+
+        let key = K::pop(state)?;
+
+        insert(key, value);
+    }
+
+    // Pop the table.
+    lua_pop(state, 1);
+
+    Ok(())
+}
+
 impl<K, V> Poppable for HashMap<K, V>
 where
     K: Poppable + Eq + Hash,
@@ -201,29 +292,34 @@ where
 
         match lua_type(state, -1) {
             LUA_TTABLE => {
-                // TODO: check that the table is an dictionary-like table and
-                // not an array-like one.
-
                 let mut map = HashMap::with_capacity(lua_objlen(state, -1));
+                pop_dict(state, |key, value| {
+                    map.insert(key, value);
+                })?;
+                Ok(map)
+            },
 
-                lua_pushnil(state);
-
-                while lua_next(state, -2) != 0 {
-                    let value = V::pop(state)?;
-
-                    // NOTE: the following `K::pop` will pop the key, so we
-                    // push another copy of the key on the stack for the next
-                    // iteration.
-                    lua_pushvalue(state, -1);
+            other => Err(Error::pop_wrong_type::<Self>(LUA_TTABLE, other)),
+        }
+    }
+}
 
-                    let key = K::pop(state)?;
+impl<K, V> Poppable for BTreeMap<K, V>
+where
+    K: Poppable + Ord,
+    V: Poppable,
+{
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        if lua_gettop(state) == 0 {
+            return Err(Error::PopEmptyStack);
+        }
 
+        match lua_type(state, -1) {
+            LUA_TTABLE => {
+                let mut map = BTreeMap::new();
+                pop_dict(state, |key, value| {
                     map.insert(key, value);
-                }
-
-                // Pop the table.
-                lua_pop(state, 1);
-
+                })?;
                 Ok(map)
             },
 
@@ -232,6 +328,62 @@ where
     }
 }
 
+/// Sets are popped from array-like tables.
+impl<T> Poppable for HashSet<T>
+where
+    T: Poppable + Eq + Hash,
+{
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        <Vec<T> as Poppable>::pop(state).map(|vec| vec.into_iter().collect())
+    }
+}
+
+/// Sets are popped from array-like tables.
+impl<T> Poppable for BTreeSet<T>
+where
+    T: Poppable + Ord,
+{
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        <Vec<T> as Poppable>::pop(state).map(|vec| vec.into_iter().collect())
+    }
+}
+
+/// Follows the Lua convention of returning `value` on success and
+/// `nil, error` on failure.
+///
+/// Since a single value can't be an error, only [`pop_many`] can return an
+/// `Err`, when exactly two values belong to the result and they're a `nil`
+/// followed by a non-`nil` value.
+///
+/// [`pop_many`]: Poppable::pop_many
+impl<T, E> Poppable for Result<T, E>
+where
+    T: Poppable,
+    E: Poppable,
+{
+    unsafe fn pop(state: *mut lua_State) -> Result<Self, Error> {
+        T::pop(state).map(Ok)
+    }
+
+    unsafe fn pop_many(
+        state: *mut lua_State,
+        n: c_int,
+    ) -> Result<Self, Error> {
+        let is_err = n == 2
+            && lua_type(state, -2) == LUA_TNIL
+            && lua_type(state, -1) != LUA_TNIL;
+
+        if is_err {
+            let err = E::pop(state)?;
+            // Pop the `nil`.
+            lua_pop(state, 1);
+            Ok(Err(err))
+        } else {
+            T::pop_many(state, n).map(Ok)
+        }
+    }
+}
+
 /// Implements `Poppable` for a tuple `(a, b, c, ..)` where all the elements
 /// in the tuple implement `Poppable`.
 macro_rules! pop_tuple {
@@ -246,6 +398,24 @@ macro_rules! pop_tuple {
                 pop_reverse!(state, $($name)*);
                 Ok(($($name,)*))
             }
+
+            #[allow(non_snake_case)]
+            unsafe fn pop_many(
+                state: *mut lua_State,
+                n: c_int,
+            ) -> Result<Self, crate::Error> {
+                // Every element but the last one takes a single value, with
+                // missing values treated as `nil`. The last one takes all the
+                // remaining values.
+                let fixed = count!($($name)*) - 1;
+
+                for _ in n..fixed {
+                    lua_pushnil(state);
+                }
+
+                pop_many_reverse!(state, (n - fixed).max(0), $($name)*);
+                Ok(($($name,)*))
+            }
         }
     );
 }
@@ -259,6 +429,17 @@ macro_rules! pop_reverse {
     ($lstate:expr,) => ();
 }
 
+macro_rules! pop_many_reverse {
+    ($lstate:expr, $rest:expr, $x:ident) => {
+        let $x = $x::pop_many($lstate, $rest)?;
+    };
+
+    ($lstate:expr, $rest:expr, $x:ident $($xs:ident)+) => {
+        pop_many_reverse!($lstate, $rest, $($xs)+);
+        let $x = $x::pop_many($lstate, 1)?;
+    };
+}
+
 pop_tuple!(A);
 pop_tuple!(A B);
 pop_tuple!(A B C);
@@ -1,7 +1,8 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::ffi::{c_char, c_int};
 
 use crate::ffi::{self, lua_Integer, lua_Number, lua_State};
-use crate::macros::count;
 
 /// Trait implemented for types that can be pushed onto the Lua stack.
 pub trait Pushable {
@@ -125,6 +126,51 @@ impl Pushable for String {
     }
 }
 
+impl Pushable for &str {
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        self.as_bytes().push(lstate)
+    }
+}
+
+impl Pushable for Cow<'_, str> {
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        self.as_ref().push(lstate)
+    }
+}
+
+/// Byte slices are pushed as Lua strings, which can contain arbitrary bytes.
+impl Pushable for &[u8] {
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        ffi::lua_pushlstring(
+            lstate,
+            self.as_ptr() as *const c_char,
+            self.len(),
+        );
+        Ok(1)
+    }
+}
+
+impl<T> Pushable for Box<T>
+where
+    T: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        (*self).push(lstate)
+    }
+}
+
 impl<T> Pushable for Option<T>
 where
     T: Pushable,
@@ -148,14 +194,168 @@ where
         self,
         lstate: *mut lua_State,
     ) -> Result<c_int, crate::Error> {
-        ffi::lua_createtable(lstate, self.len() as _, 0);
+        push_list(lstate, self.len(), self)
+    }
+}
+
+impl<T, const N: usize> Pushable for [T; N]
+where
+    T: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        push_list(lstate, self.len(), self)
+    }
+}
 
-        for (i, obj) in self.into_iter().enumerate() {
-            obj.push(lstate)?;
-            ffi::lua_rawseti(lstate, -2, (i + 1) as _);
+/// Pushes the items of an iterator as an array-like table.
+unsafe fn push_list<I>(
+    lstate: *mut lua_State,
+    len: usize,
+    items: I,
+) -> Result<c_int, crate::Error>
+where
+    I: IntoIterator,
+    I::Item: Pushable,
+{
+    ffi::lua_createtable(lstate, len as _, 0);
+
+    for (i, obj) in items.into_iter().enumerate() {
+        if let Err(err) = push_element(lstate, obj) {
+            ffi::lua_pop(lstate, 1);
+            return Err(err);
         }
+        ffi::lua_rawseti(lstate, -2, (i + 1) as _);
+    }
 
-        Ok(1)
+    Ok(1)
+}
+
+/// Pushes the key-value pairs of an iterator as a dictionary-like table.
+unsafe fn push_dict<I, K, V>(
+    lstate: *mut lua_State,
+    len: usize,
+    pairs: I,
+) -> Result<c_int, crate::Error>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Pushable,
+    V: Pushable,
+{
+    ffi::lua_createtable(lstate, 0, len as _);
+
+    for (key, value) in pairs {
+        if let Err(err) = push_element(lstate, key) {
+            ffi::lua_pop(lstate, 1);
+            return Err(err);
+        }
+
+        if let Err(err) = push_element(lstate, value) {
+            ffi::lua_pop(lstate, 2);
+            return Err(err);
+        }
+
+        ffi::lua_rawset(lstate, -3);
+    }
+
+    Ok(1)
+}
+
+/// Pushes an element of a table, which has to be a single value. Values like
+/// `Err`s, which push `nil, error`, can't be stored in a table.
+unsafe fn push_element<T: Pushable>(
+    lstate: *mut lua_State,
+    value: T,
+) -> Result<(), crate::Error> {
+    match value.push(lstate)? {
+        1 => Ok(()),
+
+        n => {
+            ffi::lua_pop(lstate, n);
+            Err(crate::Error::push_error(
+                std::any::type_name::<T>(),
+                format!(
+                    "table elements must push exactly one value, but {n} \
+                     were pushed"
+                ),
+            ))
+        },
+    }
+}
+
+impl<K, V> Pushable for HashMap<K, V>
+where
+    K: Pushable,
+    V: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        push_dict(lstate, self.len(), self)
+    }
+}
+
+impl<K, V> Pushable for BTreeMap<K, V>
+where
+    K: Pushable,
+    V: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        push_dict(lstate, self.len(), self)
+    }
+}
+
+/// Sets are pushed as array-like tables.
+impl<T> Pushable for HashSet<T>
+where
+    T: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        push_list(lstate, self.len(), self)
+    }
+}
+
+/// Sets are pushed as array-like tables, sorted in ascending order.
+impl<T> Pushable for BTreeSet<T>
+where
+    T: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        push_list(lstate, self.len(), self)
+    }
+}
+
+/// Follows the Lua convention of returning `value` on success and
+/// `nil, error` on failure.
+impl<T, E> Pushable for Result<T, E>
+where
+    T: Pushable,
+    E: Pushable,
+{
+    unsafe fn push(
+        self,
+        lstate: *mut lua_State,
+    ) -> Result<c_int, crate::Error> {
+        match self {
+            Ok(value) => value.push(lstate),
+
+            Err(err) => {
+                ffi::lua_pushnil(lstate);
+                Ok(1 + err.push(lstate)?)
+            },
+        }
     }
 }
 
@@ -173,8 +373,9 @@ macro_rules! push_tuple {
                 lstate: *mut lua_State,
             ) -> Result<c_int, crate::Error> {
                 let ($($name,)*) = self;
-                $($name.push(lstate)?;)*
-                Ok(count!($($name)*))
+                let mut pushed = 0;
+                $(pushed += $name.push(lstate)?;)*
+                Ok(pushed)
             }
         }
     }
@@ -0,0 +1,155 @@
+use std::ffi::{c_int, c_void};
+use std::marker::PhantomData;
+
+use crate::ffi::*;
+use crate::LuaTable;
+
+/// A reference to a Lua value stored in the registry. The value is kept alive
+/// until the reference is dropped, and cloning the reference creates a new
+/// one pointing to the same value.
+pub(crate) struct RegistryRef {
+    lua_ref: c_int,
+
+    // The reference can only be used from the thread owning the Lua state.
+    _not_send: PhantomData<*mut ()>,
+}
+
+impl RegistryRef {
+    pub(crate) fn from_raw(lua_ref: c_int) -> Self {
+        Self { lua_ref, _not_send: PhantomData }
+    }
+
+    /// Pops the value at the top of the stack and stores it in the registry.
+    pub(crate) unsafe fn pop(lstate: *mut lua_State) -> Self {
+        Self::from_raw(luaL_ref(lstate, LUA_REGISTRYINDEX))
+    }
+
+    /// Pushes the referenced value on top of the stack.
+    pub(crate) unsafe fn push(&self, lstate: *mut lua_State) {
+        lua_rawgeti(lstate, LUA_REGISTRYINDEX, self.lua_ref);
+    }
+
+    pub(crate) fn lua_ref(&self) -> c_int {
+        self.lua_ref
+    }
+
+    /// Returns the address of the referenced value, which is only useful for
+    /// debugging purposes.
+    pub(crate) fn as_ptr(&self) -> *const c_void {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.push(lstate);
+                let ptr = lua_topointer(lstate, -1);
+                lua_pop(lstate, 1);
+                ptr
+            })
+        }
+    }
+
+    /// Returns the metatable of the referenced value, if it has one.
+    pub(crate) fn metatable(&self) -> Option<LuaTable> {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.push(lstate);
+
+                let metatable = (lua_getmetatable(lstate, -1) != 0)
+                    .then(|| LuaTable::from_ref(Self::pop(lstate)));
+
+                lua_pop(lstate, 1);
+
+                metatable
+            })
+        }
+    }
+
+    /// Sets (or removes if `None`) the metatable of the referenced value.
+    pub(crate) fn set_metatable(&self, metatable: Option<&LuaTable>) {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.push(lstate);
+
+                match metatable {
+                    Some(table) => table.as_ref().push(lstate),
+                    None => lua_pushnil(lstate),
+                }
+
+                lua_setmetatable(lstate, -2);
+                lua_pop(lstate, 1);
+            })
+        }
+    }
+}
+
+impl Clone for RegistryRef {
+    fn clone(&self) -> Self {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.push(lstate);
+                Self::pop(lstate)
+            })
+        }
+    }
+}
+
+impl Drop for RegistryRef {
+    fn drop(&mut self) {
+        unsafe {
+            crate::with_state(|lstate| {
+                luaL_unref(lstate, LUA_REGISTRYINDEX, self.lua_ref)
+            })
+        }
+    }
+}
+
+/// Two references are equal if they point to the same Lua value.
+impl PartialEq for RegistryRef {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.push(lstate);
+                other.push(lstate);
+                let eq = lua_rawequal(lstate, -1, -2) == 1;
+                lua_pop(lstate, 2);
+                eq
+            })
+        }
+    }
+}
+
+/// Implements `Pushable` and `Poppable` for a type wrapping a `RegistryRef`
+/// to a value of the given Lua type.
+macro_rules! registry_ref_conversions {
+    ($type:ty, $lua_type:expr) => {
+        impl $crate::Pushable for $type {
+            unsafe fn push(
+                self,
+                lstate: *mut $crate::ffi::lua_State,
+            ) -> Result<::std::ffi::c_int, $crate::Error> {
+                self.as_ref().push(lstate);
+                Ok(1)
+            }
+        }
+
+        impl $crate::Poppable for $type {
+            unsafe fn pop(
+                lstate: *mut $crate::ffi::lua_State,
+            ) -> Result<Self, $crate::Error> {
+                if $crate::ffi::lua_gettop(lstate) == 0 {
+                    return Err($crate::Error::PopEmptyStack);
+                }
+
+                match $crate::ffi::lua_type(lstate, -1) {
+                    ty if ty == $lua_type => Ok(Self::from_ref(
+                        $crate::registry::RegistryRef::pop(lstate),
+                    )),
+
+                    other => Err($crate::Error::pop_wrong_type::<Self>(
+                        $lua_type, other,
+                    )),
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use registry_ref_conversions;
@@ -0,0 +1,471 @@
+use std::ffi::c_int;
+
+use serde::de::{self, IntoDeserializer};
+
+use super::Result;
+use crate::ffi::*;
+use crate::utils;
+
+/// A struct used for deserializing Lua values on the stack into Rust values.
+///
+/// The deserialized value is left on the stack.
+#[derive(Copy, Clone, Debug)]
+pub struct Deserializer {
+    lstate: *mut lua_State,
+
+    /// The absolute stack index of the value being deserialized.
+    idx: c_int,
+}
+
+impl Deserializer {
+    /// Creates a new `Deserializer` reading the value at the top of the stack
+    /// of `lstate`.
+    pub unsafe fn new(lstate: *mut lua_State) -> Self {
+        Self { lstate, idx: lua_gettop(lstate) }
+    }
+
+    /// Returns a `Deserializer` for the value at the top of the stack.
+    #[inline]
+    unsafe fn top(&self) -> Self {
+        Self::new(self.lstate)
+    }
+
+    #[inline]
+    fn ty(&self) -> c_int {
+        unsafe { lua_type(self.lstate, self.idx) }
+    }
+
+    #[inline]
+    fn invalid_type(&self, expected: &'static str) -> super::Error {
+        de::Error::invalid_type(
+            de::Unexpected::Other(utils::type_name(self.ty())),
+            &expected,
+        )
+    }
+
+    /// Returns the contents of the string at `self.idx`, assuming the value
+    /// is a string.
+    #[inline]
+    unsafe fn as_bytes(&self) -> &[u8] {
+        let mut len = 0;
+        let ptr = lua_tolstring(self.lstate, self.idx, &mut len);
+        std::slice::from_raw_parts(ptr as *const u8, len)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = super::Error;
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf unit unit_struct identifier
+    }
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.ty() {
+            LUA_TNONE | LUA_TNIL => visitor.visit_unit(),
+
+            LUA_TBOOLEAN => visitor.visit_bool(
+                unsafe { lua_toboolean(self.lstate, self.idx) } == 1,
+            ),
+
+            LUA_TNUMBER => {
+                let n = unsafe { lua_tonumber(self.lstate, self.idx) };
+
+                // Lua numbers are always floats, so we try to give integral
+                // ones back as integers.
+                if n.fract() == 0.0
+                    && n >= i64::MIN as f64
+                    && n <= i64::MAX as f64
+                {
+                    visitor.visit_i64(n as i64)
+                } else {
+                    visitor.visit_f64(n)
+                }
+            },
+
+            LUA_TSTRING => {
+                let bytes = unsafe { self.as_bytes() };
+                match std::str::from_utf8(bytes) {
+                    Ok(str) => visitor.visit_str(str),
+                    _ => visitor.visit_bytes(bytes),
+                }
+            },
+
+            LUA_TTABLE => {
+                let rel_idx =
+                    self.idx - unsafe { lua_gettop(self.lstate) } - 1;
+
+                if unsafe { utils::is_table_array(self.lstate, rel_idx) } {
+                    self.deserialize_seq(visitor)
+                } else {
+                    self.deserialize_map(visitor)
+                }
+            },
+
+            // Functions can only be deserialized as the `LUA_REF_NEWTYPE`
+            // newtype, see `deserialize_newtype_struct`. Storing them in the
+            // registry here would leak the reference if the visitor rejected
+            // it.
+            _ => Err(self.invalid_type("a value representable in Rust")),
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.ty() {
+            LUA_TNONE | LUA_TNIL => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        _name: &str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.ty() {
+            LUA_TSTRING => {
+                let variant =
+                    String::from_utf8(unsafe { self.as_bytes() }.to_owned())
+                        .map_err(de::Error::custom)?;
+                visitor.visit_enum(EnumDeserializer { variant, value: None })
+            },
+
+            LUA_TTABLE => unsafe {
+                let top = lua_gettop(self.lstate);
+                let res = self.deserialize_variant_table(visitor);
+                lua_settop(self.lstate, top);
+                res
+            },
+
+            _ => Err(self.invalid_type("string or table")),
+        }
+    }
+
+    #[inline]
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.ty() {
+            LUA_TTABLE => {
+                let len = unsafe { lua_objlen(self.lstate, self.idx) };
+                let mut deserializer =
+                    SeqDeserializer { de: self, next: 1, len: len as _ };
+                visitor.visit_seq(&mut deserializer)
+            },
+
+            _ => Err(self.invalid_type("table")),
+        }
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.ty() {
+            LUA_TTABLE => unsafe {
+                let top = lua_gettop(self.lstate);
+
+                // The first key passed to `lua_next` has to be `nil`.
+                lua_pushnil(self.lstate);
+
+                let mut deserializer = MapDeserializer { de: self };
+                let res = visitor.visit_map(&mut deserializer);
+
+                // Remove the last key if the visitor stopped early.
+                lua_settop(self.lstate, top);
+
+                res
+            },
+
+            _ => Err(self.invalid_type("table")),
+        }
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if name != super::LUA_REF_NEWTYPE {
+            return visitor.visit_newtype_struct(self);
+        }
+
+        match self.ty() {
+            LUA_TFUNCTION => {
+                let lua_ref = unsafe {
+                    lua_pushvalue(self.lstate, self.idx);
+                    luaL_ref(self.lstate, LUA_REGISTRYINDEX)
+                };
+                visitor.visit_newtype_struct(lua_ref.into_deserializer())
+            },
+
+            _ => Err(self.invalid_type("a function")),
+        }
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Going through `deserialize_any` would store functions in the
+        // registry without ever removing them.
+        visitor.visit_unit()
+    }
+}
+
+impl Deserializer {
+    /// Deserializes an enum variant represented as `{ variant = value }`,
+    /// leaving the key and the value on the stack.
+    unsafe fn deserialize_variant_table<'de, V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let single_pair = || {
+            de::Error::invalid_value(
+                de::Unexpected::Map,
+                &"table with a single key-value pair",
+            )
+        };
+
+        lua_pushnil(self.lstate);
+
+        if lua_next(self.lstate, self.idx) == 0 {
+            return Err(single_pair());
+        }
+
+        // Check that there's no other key after this one.
+        lua_pushvalue(self.lstate, -2);
+        if lua_next(self.lstate, self.idx) != 0 {
+            return Err(single_pair());
+        }
+
+        let key =
+            Self { lstate: self.lstate, idx: lua_gettop(self.lstate) - 1 };
+
+        if key.ty() != LUA_TSTRING {
+            return Err(key.invalid_type("string"));
+        }
+
+        let variant = String::from_utf8(key.as_bytes().to_owned())
+            .map_err(de::Error::custom)?;
+
+        visitor
+            .visit_enum(EnumDeserializer { variant, value: Some(self.top()) })
+    }
+}
+
+struct SeqDeserializer {
+    de: Deserializer,
+    next: c_int,
+    len: c_int,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer {
+    type Error = super::Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.next > self.len {
+            return Ok(None);
+        }
+
+        let lstate = self.de.lstate;
+
+        unsafe { lua_rawgeti(lstate, self.de.idx, self.next) };
+        let res = seed.deserialize(unsafe { self.de.top() });
+        unsafe { lua_pop(lstate, 1) };
+
+        self.next += 1;
+
+        res.map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.next + 1) as usize)
+    }
+}
+
+/// Iterates over the pairs of a table using `lua_next`. Between a call to
+/// `next_key_seed` and one to `next_value_seed` the current key and value
+/// are at the top of the stack, after it only the key is.
+struct MapDeserializer {
+    de: Deserializer,
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer {
+    type Error = super::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let lstate = self.de.lstate;
+
+        if unsafe { lua_next(lstate, self.de.idx) } == 0 {
+            return Ok(None);
+        }
+
+        let key =
+            Deserializer { lstate, idx: unsafe { lua_gettop(lstate) } - 1 };
+
+        seed.deserialize(key).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let lstate = self.de.lstate;
+
+        let res = seed.deserialize(unsafe { self.de.top() });
+
+        // Pop the value, leaving the key for the next call to `lua_next`.
+        unsafe { lua_pop(lstate, 1) };
+
+        res
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<Deserializer>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer {
+    type Error = super::Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = self.variant.into_deserializer();
+        let deserializer = VariantDeserializer { value: self.value };
+        seed.deserialize(variant).map(|v| (v, deserializer))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<Deserializer>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer {
+    type Error = super::Error;
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.value {
+            Some(de) => seed.deserialize(de),
+
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"newtype variant",
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(de) => de::Deserializer::deserialize_map(de, visitor),
+
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"struct variant",
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.value {
+            Some(de) => de::Deserializer::deserialize_seq(de, visitor),
+
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &"tuple variant",
+            )),
+        }
+    }
+
+    fn unit_variant(self) -> Result<()> {
+        match self.value {
+            None => Ok(()),
+
+            _ => Err(de::Error::invalid_type(
+                de::Unexpected::NewtypeVariant,
+                &"unit variant",
+            )),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+
+use serde::{de, ser};
+use thiserror::Error as ThisError;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Clone, Debug, Eq, PartialEq, ThisError)]
+pub enum Error {
+    #[error("{0}")]
+    Serialize(String),
+
+    #[error("{0}")]
+    Deserialize(String),
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Serialize(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self::Deserialize(msg.to_string())
+    }
+}
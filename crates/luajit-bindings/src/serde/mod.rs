@@ -0,0 +1,75 @@
+//! (De)Serialization support for Lua values using [Serde].
+//!
+//! Unlike going through Neovim's `Object`s, the [`Serializer`] and
+//! [`Deserializer`] defined here write to and read from the Lua stack
+//! directly, without allocating any intermediate representation.
+//!
+//! [Serde]: https://serde.rs/
+
+mod de;
+mod error;
+mod ser;
+
+use std::ffi::c_int;
+
+pub use de::Deserializer;
+pub use error::{Error, Result};
+pub use ser::Serializer;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::ffi::*;
+
+/// The name of the newtype struct used to (de)serialize references to values
+/// stored in the Lua registry, e.g. `nvim_types::Function`s.
+///
+/// When serializing, the referenced value is pushed instead of the integer.
+/// When deserializing, the value is stored in the registry and its reference
+/// is passed to the visitor.
+#[doc(hidden)]
+pub const LUA_REF_NEWTYPE: &str = "$__nvim_oxi_LuaRef";
+
+/// Serializes `value` and pushes it on the stack, returning the number of
+/// values pushed (always `1`). If the serialization fails the stack is left
+/// untouched.
+///
+/// This can be used to implement [`Pushable`](crate::Pushable) for any type
+/// implementing [`Serialize`].
+pub unsafe fn push<T>(
+    lstate: *mut lua_State,
+    value: &T,
+) -> std::result::Result<c_int, crate::Error>
+where
+    T: Serialize,
+{
+    let top = lua_gettop(lstate);
+
+    match value.serialize(Serializer::new(lstate)) {
+        Ok(()) => Ok(1),
+
+        Err(err) => {
+            lua_settop(lstate, top);
+            Err(crate::Error::push_error_from_err::<T, _>(err))
+        },
+    }
+}
+
+/// Deserializes the value at the top of the stack and pops it.
+///
+/// This can be used to implement [`Poppable`](crate::Poppable) for any type
+/// implementing [`Deserialize`](serde::Deserialize).
+pub unsafe fn pop<T>(
+    lstate: *mut lua_State,
+) -> std::result::Result<T, crate::Error>
+where
+    T: DeserializeOwned,
+{
+    if lua_gettop(lstate) == 0 {
+        return Err(crate::Error::PopEmptyStack);
+    }
+
+    let res = T::deserialize(Deserializer::new(lstate));
+
+    lua_pop(lstate, 1);
+
+    res.map_err(crate::Error::pop_error_from_err::<T, _>)
+}
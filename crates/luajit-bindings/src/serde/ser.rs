@@ -0,0 +1,391 @@
+use std::ffi::c_int;
+
+use serde::ser::{self, Error};
+
+use super::Result;
+use crate::ffi::*;
+
+/// A struct for serializing Rust values directly onto the Lua stack.
+///
+/// Every successful call to one of the `serialize_*` methods pushes exactly
+/// one value on the stack.
+#[derive(Copy, Clone, Debug)]
+pub struct Serializer {
+    lstate: *mut lua_State,
+}
+
+impl Serializer {
+    /// Creates a new `Serializer` pushing values on the stack of `lstate`.
+    pub unsafe fn new(lstate: *mut lua_State) -> Self {
+        Self { lstate }
+    }
+}
+
+macro_rules! serialize_int {
+    ($name:ident, $type:ty) => {
+        #[inline]
+        fn $name(self, value: $type) -> Result<()> {
+            unsafe { lua_pushinteger(self.lstate, value as lua_Integer) };
+            Ok(())
+        }
+    };
+}
+
+macro_rules! serialize_big_int {
+    ($name:ident, $type:ty) => {
+        #[inline]
+        fn $name(self, value: $type) -> Result<()> {
+            let value = lua_Integer::try_from(value).map_err(|_| {
+                super::Error::custom(format!(
+                    "{value} is too big to be represented as a Lua integer"
+                ))
+            })?;
+            unsafe { lua_pushinteger(self.lstate, value) };
+            Ok(())
+        }
+    };
+}
+
+macro_rules! serialize_nil {
+    ($name:ident) => {
+        #[inline]
+        fn $name(self) -> Result<()> {
+            unsafe { lua_pushnil(self.lstate) };
+            Ok(())
+        }
+    };
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = ();
+    type Error = super::Error;
+
+    type SerializeSeq = SerializeSeq;
+    type SerializeTuple = SerializeSeq;
+    type SerializeTupleStruct = SerializeSeq;
+    type SerializeTupleVariant = SerializeSeq;
+
+    type SerializeMap = SerializeMap;
+    type SerializeStruct = SerializeMap;
+    type SerializeStructVariant = SerializeMap;
+
+    serialize_int!(serialize_i8, i8);
+    serialize_int!(serialize_u8, u8);
+    serialize_int!(serialize_i16, i16);
+    serialize_int!(serialize_u16, u16);
+    serialize_int!(serialize_i32, i32);
+    serialize_int!(serialize_u32, u32);
+
+    serialize_big_int!(serialize_i64, i64);
+    serialize_big_int!(serialize_u64, u64);
+    serialize_big_int!(serialize_i128, i128);
+    serialize_big_int!(serialize_u128, u128);
+
+    serialize_nil!(serialize_none);
+    serialize_nil!(serialize_unit);
+
+    #[inline]
+    fn serialize_bool(self, value: bool) -> Result<()> {
+        unsafe { lua_pushboolean(self.lstate, value as _) };
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_f32(self, value: f32) -> Result<()> {
+        self.serialize_f64(value.into())
+    }
+
+    #[inline]
+    fn serialize_f64(self, value: f64) -> Result<()> {
+        unsafe { lua_pushnumber(self.lstate, value) };
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_char(self, value: char) -> Result<()> {
+        self.serialize_str(value.encode_utf8(&mut [0; 4]))
+    }
+
+    #[inline]
+    fn serialize_str(self, value: &str) -> Result<()> {
+        self.serialize_bytes(value.as_bytes())
+    }
+
+    #[inline]
+    fn serialize_bytes(self, value: &[u8]) -> Result<()> {
+        unsafe {
+            lua_pushlstring(
+                self.lstate,
+                value.as_ptr() as *const _,
+                value.len(),
+            )
+        };
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        value.serialize(self)
+    }
+
+    #[inline]
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    #[inline]
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.serialize_str(variant)
+    }
+
+    #[inline]
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        value.serialize(self)?;
+
+        // Replace the reference with the value it points to.
+        if name == super::LUA_REF_NEWTYPE {
+            unsafe {
+                let lua_ref = lua_tointeger(self.lstate, -1) as c_int;
+                lua_pop(self.lstate, 1);
+                lua_rawgeti(self.lstate, LUA_REGISTRYINDEX, lua_ref);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Newtype variants are serialized as `{ variant = value }`.
+    #[inline]
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        self.begin_variant(variant)?;
+        value.serialize(self)?;
+        unsafe { lua_rawset(self.lstate, -3) };
+        Ok(())
+    }
+
+    #[inline]
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
+        let len = len.unwrap_or_default();
+        unsafe { lua_createtable(self.lstate, len as _, 0) };
+        Ok(SerializeSeq { lstate: self.lstate, len: 0, is_variant: false })
+    }
+
+    #[inline]
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    #[inline]
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    /// Tuple variants are serialized as `{ variant = { .. } }`.
+    #[inline]
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        self.begin_variant(variant)?;
+        let seq = self.serialize_seq(Some(len))?;
+        Ok(SerializeSeq { is_variant: true, ..seq })
+    }
+
+    #[inline]
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
+        let len = len.unwrap_or_default();
+        unsafe { lua_createtable(self.lstate, 0, len as _) };
+        Ok(SerializeMap { lstate: self.lstate, is_variant: false })
+    }
+
+    #[inline]
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        self.serialize_map(Some(len))
+    }
+
+    /// Struct variants are serialized as `{ variant = { .. } }`.
+    #[inline]
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        self.begin_variant(variant)?;
+        let map = self.serialize_map(Some(len))?;
+        Ok(SerializeMap { is_variant: true, ..map })
+    }
+}
+
+impl Serializer {
+    /// Pushes the outer table of an externally tagged enum variant followed
+    /// by the name of the variant. The caller is responsible for pushing the
+    /// value and setting it in the table.
+    #[inline]
+    fn begin_variant(self, variant: &'static str) -> Result<()> {
+        unsafe { lua_createtable(self.lstate, 0, 1) };
+        ser::Serializer::serialize_str(self, variant)
+    }
+}
+
+pub struct SerializeSeq {
+    lstate: *mut lua_State,
+    len: c_int,
+    is_variant: bool,
+}
+
+impl ser::SerializeSeq for SerializeSeq {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        value.serialize(Serializer { lstate: self.lstate })?;
+        self.len += 1;
+        unsafe { lua_rawseti(self.lstate, -2, self.len) };
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        if self.is_variant {
+            unsafe { lua_rawset(self.lstate, -3) };
+        }
+        Ok(())
+    }
+}
+
+macro_rules! serialize_seq {
+    ($trait:ident, $fn:ident) => {
+        impl ser::$trait for SerializeSeq {
+            type Ok = ();
+            type Error = super::Error;
+
+            fn $fn<T>(&mut self, value: &T) -> Result<()>
+            where
+                T: ser::Serialize + ?Sized,
+            {
+                ser::SerializeSeq::serialize_element(self, value)
+            }
+
+            fn end(self) -> Result<()> {
+                ser::SerializeSeq::end(self)
+            }
+        }
+    };
+}
+
+serialize_seq!(SerializeTuple, serialize_element);
+serialize_seq!(SerializeTupleStruct, serialize_field);
+serialize_seq!(SerializeTupleVariant, serialize_field);
+
+pub struct SerializeMap {
+    lstate: *mut lua_State,
+    is_variant: bool,
+}
+
+impl ser::SerializeMap for SerializeMap {
+    type Ok = ();
+    type Error = super::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        key.serialize(Serializer { lstate: self.lstate })?;
+
+        // Setting a `nil` or NaN key would raise a Lua error.
+        match unsafe { lua_type(self.lstate, -1) } {
+            LUA_TNIL => Err(super::Error::custom("map keys can't be nil")),
+
+            LUA_TNUMBER
+                if unsafe { lua_tonumber(self.lstate, -1) }.is_nan() =>
+            {
+                Err(super::Error::custom("map keys can't be NaN"))
+            },
+
+            _ => Ok(()),
+        }
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ser::Serialize + ?Sized,
+    {
+        value.serialize(Serializer { lstate: self.lstate })?;
+        unsafe { lua_rawset(self.lstate, -3) };
+        Ok(())
+    }
+
+    fn end(self) -> Result<()> {
+        if self.is_variant {
+            unsafe { lua_rawset(self.lstate, -3) };
+        }
+        Ok(())
+    }
+}
+
+macro_rules! serialize_map {
+    ($trait:ident) => {
+        impl ser::$trait for SerializeMap {
+            type Ok = ();
+            type Error = super::Error;
+
+            fn serialize_field<T>(
+                &mut self,
+                key: &'static str,
+                value: &T,
+            ) -> Result<()>
+            where
+                T: ser::Serialize + ?Sized,
+            {
+                ser::SerializeMap::serialize_key(self, key)?;
+                ser::SerializeMap::serialize_value(self, value)
+            }
+
+            fn end(self) -> Result<()> {
+                ser::SerializeMap::end(self)
+            }
+        }
+    };
+}
+
+serialize_map!(SerializeStruct);
+serialize_map!(SerializeStructVariant);
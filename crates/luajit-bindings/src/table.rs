@@ -0,0 +1,309 @@
+use std::ffi::c_int;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::ffi::*;
+use crate::registry::{registry_ref_conversions, RegistryRef};
+use crate::{Error, LuaValue, Poppable, Pushable};
+
+/// A reference to a Lua table stored in the registry, which keeps the table
+/// alive for as long as it exists.
+///
+/// Unlike converting a table into a `HashMap` or a `Vec`, this keeps the
+/// values it contains as they are, including functions and nested tables.
+///
+/// All the accessors use raw access, i.e. they don't trigger any of the
+/// table's metamethods.
+#[derive(Clone, PartialEq)]
+pub struct LuaTable {
+    inner: RegistryRef,
+}
+
+impl fmt::Debug for LuaTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "table: {:p}", self.inner.as_ptr())
+    }
+}
+
+impl Default for LuaTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+registry_ref_conversions!(LuaTable, LUA_TTABLE);
+
+impl LuaTable {
+    pub(crate) fn from_ref(inner: RegistryRef) -> Self {
+        Self { inner }
+    }
+
+    pub(crate) fn as_ref(&self) -> &RegistryRef {
+        &self.inner
+    }
+
+    /// Creates a new empty table.
+    pub fn new() -> Self {
+        unsafe {
+            crate::with_state(|lstate| {
+                lua_createtable(lstate, 0, 0);
+                Self::from_ref(RegistryRef::pop(lstate))
+            })
+        }
+    }
+
+    /// Returns the value associated to `key`, which is `nil` if the key is
+    /// not present.
+    pub fn get<K, V>(&self, key: K) -> Result<V, Error>
+    where
+        K: Pushable,
+        V: Poppable,
+    {
+        unsafe {
+            crate::with_state(|lstate| {
+                let top = lua_gettop(lstate);
+
+                self.inner.push(lstate);
+
+                let res = push_key(lstate, key).and_then(|()| {
+                    lua_rawget(lstate, -2);
+                    V::pop(lstate)
+                });
+
+                lua_settop(lstate, top);
+
+                res
+            })
+        }
+    }
+
+    /// Associates `value` to `key`. Setting a key to `nil` removes it from
+    /// the table.
+    pub fn set<K, V>(&self, key: K, value: V) -> Result<(), Error>
+    where
+        K: Pushable,
+        V: Pushable,
+    {
+        unsafe {
+            crate::with_state(|lstate| {
+                let top = lua_gettop(lstate);
+
+                self.inner.push(lstate);
+
+                let res = push_key(lstate, key).and_then(|()| {
+                    match value.push(lstate)? {
+                        1 => {
+                            lua_rawset(lstate, -3);
+                            Ok(())
+                        },
+
+                        n => Err(Error::push_error(
+                            std::any::type_name::<V>(),
+                            format!(
+                                "table values must be a single Lua value, \
+                                 got {n}"
+                            ),
+                        )),
+                    }
+                });
+
+                lua_settop(lstate, top);
+
+                res
+            })
+        }
+    }
+
+    /// Returns the length of the table, as returned by the `#` operator.
+    pub fn len(&self) -> usize {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.inner.push(lstate);
+                let len = lua_objlen(lstate, -1);
+                lua_pop(lstate, 1);
+                len
+            })
+        }
+    }
+
+    /// Returns `true` if the table doesn't contain any key.
+    pub fn is_empty(&self) -> bool {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.inner.push(lstate);
+                lua_pushnil(lstate);
+
+                let is_empty = lua_next(lstate, -2) == 0;
+
+                // `lua_next` pushes a key and a value if the table isn't
+                // empty.
+                lua_pop(lstate, if is_empty { 1 } else { 3 });
+
+                is_empty
+            })
+        }
+    }
+
+    /// Returns an iterator over all the key-value pairs of the table, in no
+    /// particular order, like Lua's `pairs`.
+    pub fn pairs<K, V>(&self) -> Pairs<K, V>
+    where
+        K: Poppable,
+        V: Poppable,
+    {
+        Pairs { table: self.clone(), key: None, done: false, _pd: PhantomData }
+    }
+
+    /// Returns an iterator over the values at the keys `1, 2, ..` of the
+    /// table, stopping at the first `nil`, like Lua's `ipairs`.
+    pub fn ipairs<V>(&self) -> Ipairs<V>
+    where
+        V: Poppable,
+    {
+        Ipairs { table: self.clone(), next: 1, done: false, _pd: PhantomData }
+    }
+
+    /// Returns the metatable of the table, if it has one.
+    pub fn metatable(&self) -> Option<LuaTable> {
+        self.inner.metatable()
+    }
+
+    /// Sets the metatable of the table, removing it if `None`.
+    pub fn set_metatable(&self, metatable: Option<&LuaTable>) {
+        self.inner.set_metatable(metatable)
+    }
+}
+
+/// Pushes a value to be used as a table key, checking that it's a single
+/// value that Lua accepts as a key.
+unsafe fn push_key<K: Pushable>(
+    lstate: *mut lua_State,
+    key: K,
+) -> Result<(), Error> {
+    let ty = std::any::type_name::<K>();
+
+    let n = key.push(lstate)?;
+
+    if n != 1 {
+        return Err(Error::push_error(
+            ty,
+            format!("table keys must be a single Lua value, got {n}"),
+        ));
+    }
+
+    match lua_type(lstate, -1) {
+        LUA_TNIL => Err(Error::push_error(ty, "table keys can't be nil")),
+
+        LUA_TNUMBER if lua_tonumber(lstate, -1).is_nan() => {
+            Err(Error::push_error(ty, "table keys can't be NaN"))
+        },
+
+        _ => Ok(()),
+    }
+}
+
+/// Iterator over the key-value pairs of a [`LuaTable`], returned by
+/// [`LuaTable::pairs`].
+pub struct Pairs<K, V> {
+    table: LuaTable,
+
+    /// The last key returned by `lua_next`, needed to get the next one.
+    key: Option<LuaValue>,
+
+    done: bool,
+    _pd: PhantomData<(K, V)>,
+}
+
+impl<K, V> Iterator for Pairs<K, V>
+where
+    K: Poppable,
+    V: Poppable,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        unsafe {
+            crate::with_state(|lstate| {
+                let top = lua_gettop(lstate);
+
+                self.table.inner.push(lstate);
+
+                match self.key.take() {
+                    Some(key) => key.push_ref(lstate),
+                    None => lua_pushnil(lstate),
+                }
+
+                if lua_next(lstate, -2) == 0 {
+                    self.done = true;
+                    lua_settop(lstate, top);
+                    return None;
+                }
+
+                // Store a copy of the key for the next iteration before
+                // popping it, since popping could convert it in place.
+                lua_pushvalue(lstate, -2);
+                self.key = LuaValue::pop(lstate).ok();
+
+                let pair = V::pop(lstate)
+                    .and_then(|value| K::pop(lstate).map(|key| (key, value)));
+
+                lua_settop(lstate, top);
+
+                if self.key.is_none() {
+                    self.done = true;
+                }
+
+                Some(pair)
+            })
+        }
+    }
+}
+
+/// Iterator over the array part of a [`LuaTable`], returned by
+/// [`LuaTable::ipairs`].
+pub struct Ipairs<V> {
+    table: LuaTable,
+    next: c_int,
+    done: bool,
+    _pd: PhantomData<V>,
+}
+
+impl<V> Iterator for Ipairs<V>
+where
+    V: Poppable,
+{
+    type Item = Result<V, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        unsafe {
+            crate::with_state(|lstate| {
+                let top = lua_gettop(lstate);
+
+                self.table.inner.push(lstate);
+                lua_rawgeti(lstate, -1, self.next);
+
+                if lua_type(lstate, -1) == LUA_TNIL {
+                    self.done = true;
+                    lua_settop(lstate, top);
+                    return None;
+                }
+
+                let value = V::pop(lstate);
+
+                lua_settop(lstate, top);
+
+                self.next += 1;
+
+                Some(value)
+            })
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::cell::Cell;
+use std::ffi::c_int;
+use std::fmt;
+
+use crate::ffi::*;
+use crate::function::{error_message, runtime_error};
+use crate::registry::{registry_ref_conversions, RegistryRef};
+use crate::{Error, LuaFunction, Poppable, Pushable};
+
+thread_local! {
+    /// Whether the Rust function currently being called by Lua returned a
+    /// [`Yield`].
+    static YIELDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Returns whether the Rust function that just returned wants to yield,
+/// resetting the flag.
+pub(crate) fn take_yield() -> bool {
+    YIELDING.with(|y| y.replace(false))
+}
+
+/// A reference to a Lua coroutine stored in the registry, which keeps it
+/// alive for as long as it exists.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua::{self, LuaFunction, LuaThread};
+///
+/// let fun = lua::call_path::<_, LuaFunction>(
+///     "loadstring",
+///     "local a = ...; local b = coroutine.yield(a + 1); return a + b",
+/// )?;
+///
+/// let thread = LuaThread::new(&fun);
+/// assert_eq!(Ok(2), thread.resume::<_, i32>(1));
+/// assert_eq!(Ok(11), thread.resume::<_, i32>(10));
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct LuaThread {
+    inner: RegistryRef,
+}
+
+/// The status of a [`LuaThread`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ThreadStatus {
+    /// The coroutine hasn't started yet, or it's suspended in a yield.
+    Resumable,
+
+    /// The coroutine is running, or it resumed another coroutine and is
+    /// waiting for it to yield or return (`"running"` and `"normal"` in
+    /// `coroutine.status`).
+    Running,
+
+    /// The coroutine returned.
+    Finished,
+
+    /// The coroutine raised an error.
+    Error,
+}
+
+impl fmt::Debug for LuaThread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "thread: {:p}", self.inner.as_ptr())
+    }
+}
+
+registry_ref_conversions!(LuaThread, LUA_TTHREAD);
+
+impl LuaThread {
+    pub(crate) fn from_ref(inner: RegistryRef) -> Self {
+        Self { inner }
+    }
+
+    pub(crate) fn as_ref(&self) -> &RegistryRef {
+        &self.inner
+    }
+
+    /// Creates a new coroutine executing `fun`, like `coroutine.create`.
+    pub fn new(fun: &LuaFunction) -> Self {
+        unsafe {
+            crate::with_state(|lstate| {
+                let co = lua_newthread(lstate);
+                fun.as_ref().push(lstate);
+                lua_xmove(lstate, co, 1);
+                Self::from_ref(RegistryRef::pop(lstate))
+            })
+        }
+    }
+
+    /// Returns the status of the coroutine.
+    pub fn status(&self) -> ThreadStatus {
+        unsafe { crate::with_state(|lstate| status(self.to_state(lstate))) }
+    }
+
+    /// Starts or continues the execution of the coroutine, like
+    /// `coroutine.resume`.
+    ///
+    /// The first time the coroutine is resumed `args` are passed to its
+    /// function, after that they are returned by the `coroutine.yield` it's
+    /// suspended in. The values passed to the next yield (or returned by the
+    /// function) are popped into `R`.
+    pub fn resume<A, R>(&self, args: A) -> Result<R, Error>
+    where
+        A: Pushable,
+        R: Poppable,
+    {
+        unsafe {
+            crate::with_state(|lstate| {
+                let co = self.to_state(lstate);
+
+                let message = match status(co) {
+                    ThreadStatus::Resumable => None,
+                    ThreadStatus::Running => {
+                        Some("cannot resume non-suspended coroutine")
+                    },
+                    ThreadStatus::Finished | ThreadStatus::Error => {
+                        Some("cannot resume dead coroutine")
+                    },
+                };
+
+                if let Some(message) = message {
+                    return Err(Error::RuntimeError {
+                        message: message.to_owned(),
+                        traceback: None,
+                    });
+                }
+
+                let top = lua_gettop(co);
+
+                let nargs = match args.push(co) {
+                    Ok(nargs) => nargs,
+
+                    Err(err) => {
+                        lua_settop(co, top);
+                        return Err(err);
+                    },
+                };
+
+                match lua_resume(co, nargs) {
+                    LUA_OK | LUA_YIELD => {
+                        let ret = R::pop_many(co, lua_gettop(co));
+
+                        // The stack of a suspended coroutine must only
+                        // contain the values passed to the next resume.
+                        lua_settop(co, 0);
+
+                        ret
+                    },
+
+                    LUA_ERRMEM => {
+                        let msg = error_message(co);
+                        lua_settop(co, 0);
+                        Err(Error::MemoryError(msg))
+                    },
+
+                    _ => {
+                        // The traceback has to be generated before the
+                        // coroutine's stack is unwound.
+                        // The message is added on the Rust side since it
+                        // could contain NUL bytes.
+                        let msg = error_message(co);
+                        luaL_traceback(lstate, co, std::ptr::null(), 0);
+                        let traceback = error_message(lstate);
+                        lua_pop(lstate, 1);
+                        lua_settop(co, 0);
+                        Err(runtime_error(format!("{msg}\n{traceback}")))
+                    },
+                }
+            })
+        }
+    }
+
+    /// Returns the state of the coroutine. The registry keeps it alive.
+    unsafe fn to_state(&self, lstate: *mut lua_State) -> *mut lua_State {
+        self.inner.push(lstate);
+        let co = lua_tothread(lstate, -1);
+        lua_pop(lstate, 1);
+        co
+    }
+}
+
+unsafe fn status(co: *mut lua_State) -> ThreadStatus {
+    // Same as `auxstatus` in Lua 5.1: a coroutine with an active frame is
+    // either running or waiting for a coroutine it resumed. Its stack must
+    // not be touched.
+    let mut ar = std::mem::MaybeUninit::<lua_Debug>::uninit();
+
+    match lua_status(co) {
+        LUA_YIELD => ThreadStatus::Resumable,
+        LUA_OK if lua_getstack(co, 0, ar.as_mut_ptr()) > 0 => {
+            ThreadStatus::Running
+        },
+        // A coroutine that hasn't started yet has its function on the stack.
+        LUA_OK if lua_gettop(co) > 0 => ThreadStatus::Resumable,
+        LUA_OK => ThreadStatus::Finished,
+        _ => ThreadStatus::Error,
+    }
+}
+
+/// Returned by a Rust function called from a Lua coroutine to yield the
+/// wrapped values to whoever resumed it, like `coroutine.yield`.
+///
+/// When the coroutine is resumed again the values passed to the resume are
+/// returned to the Lua code that called the Rust function. Yielding from a
+/// function that wasn't called from inside a coroutine results in an error.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua::{LuaFunction, Yield};
+///
+/// // Equivalent to `function(n) return coroutine.yield(n * 2) end`.
+/// let double = LuaFunction::from_fn(|n: i32| {
+///     Ok::<_, nvim_oxi::lua::Error>(Yield(n * 2))
+/// });
+/// ```
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Yield<T>(pub T);
+
+impl<T: Pushable> Pushable for Yield<T> {
+    unsafe fn push(self, lstate: *mut lua_State) -> Result<c_int, Error> {
+        let nvalues = self.0.push(lstate)?;
+        YIELDING.with(|y| y.set(true));
+        Ok(nvalues)
+    }
+}
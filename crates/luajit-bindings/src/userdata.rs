@@ -0,0 +1,611 @@
+use std::any::{type_name, TypeId};
+use std::cell::{Ref, RefCell, RefMut};
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::ffi::{c_int, c_void};
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::ffi::*;
+use crate::function::{self, Callback};
+use crate::registry::{registry_ref_conversions, RegistryRef};
+use crate::{Error, LuaTable, Poppable, Pushable};
+
+/// A reference to a full Lua userdata stored in the registry, which keeps it
+/// alive for as long as it exists.
+#[derive(Clone, PartialEq)]
+pub struct LuaUserData {
+    inner: RegistryRef,
+}
+
+impl fmt::Debug for LuaUserData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "userdata: {:p}", self.as_ptr())
+    }
+}
+
+registry_ref_conversions!(LuaUserData, LUA_TUSERDATA);
+
+impl LuaUserData {
+    pub(crate) fn from_ref(inner: RegistryRef) -> Self {
+        Self { inner }
+    }
+
+    pub(crate) fn as_ref(&self) -> &RegistryRef {
+        &self.inner
+    }
+
+    /// Returns a pointer to the memory block of the userdata.
+    pub fn as_ptr(&self) -> *mut c_void {
+        unsafe {
+            crate::with_state(|lstate| {
+                self.inner.push(lstate);
+                let ptr = lua_touserdata(lstate, -1);
+                lua_pop(lstate, 1);
+                ptr
+            })
+        }
+    }
+
+    /// Returns the metatable of the userdata, if it has one.
+    pub fn metatable(&self) -> Option<LuaTable> {
+        self.inner.metatable()
+    }
+
+    /// Sets the metatable of the userdata, removing it if `None`.
+    pub fn set_metatable(&self, metatable: Option<&LuaTable>) {
+        self.inner.set_metatable(metatable)
+    }
+}
+
+/// Trait implemented by Rust types that can be exposed to Lua as userdata,
+/// together with their methods and metamethods.
+///
+/// Values are moved into Lua via [`UserDataRef::new`], and are dropped when
+/// Lua garbage collects them.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua::{Error, MetaMethod, UserData, UserDataMethods};
+///
+/// struct Counter(u32);
+///
+/// impl UserData for Counter {
+///     fn add_methods(methods: &mut UserDataMethods<Self>) {
+///         methods.add_method("get", |this, ()| Ok::<_, Error>(this.0));
+///
+///         methods.add_method_mut("incr", |this, by: u32| {
+///             this.0 += by;
+///             Ok::<_, Error>(())
+///         });
+///
+///         methods.add_meta_method(MetaMethod::ToString, |this, ()| {
+///             Ok::<_, Error>(format!("Counter({})", this.0))
+///         });
+///     }
+/// }
+/// ```
+pub trait UserData: Sized + 'static {
+    /// Registers the methods and metamethods of the type. It's only called
+    /// once, the first time a value of the type is moved into Lua.
+    fn add_methods(_methods: &mut UserDataMethods<Self>) {}
+}
+
+/// The metamethods that can be set on a [`UserData`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MetaMethod {
+    /// `__add`, the `+` operator.
+    Add,
+
+    /// `__call`, calling the value like a function.
+    Call,
+
+    /// `__concat`, the `..` operator.
+    Concat,
+
+    /// `__div`, the `/` operator.
+    Div,
+
+    /// `__eq`, the `==` operator.
+    Eq,
+
+    /// `__gc`, called right before the value is dropped.
+    Gc,
+
+    /// `__index`, called when indexing the value with a key that isn't the
+    /// name of one of its methods.
+    Index,
+
+    /// `__le`, the `<=` operator.
+    Le,
+
+    /// `__len`, the `#` operator.
+    Len,
+
+    /// `__lt`, the `<` operator.
+    Lt,
+
+    /// `__mod`, the `%` operator.
+    Mod,
+
+    /// `__mul`, the `*` operator.
+    Mul,
+
+    /// `__newindex`, assigning to a field of the value.
+    NewIndex,
+
+    /// `__pow`, the `^` operator.
+    Pow,
+
+    /// `__sub`, the binary `-` operator.
+    Sub,
+
+    /// `__tostring`, converting the value to a string via `tostring`.
+    ToString,
+
+    /// `__unm`, the unary `-` operator.
+    Unm,
+}
+
+impl MetaMethod {
+    /// Returns the name of the metamethod, e.g. `"__index"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Add => "__add",
+            Self::Call => "__call",
+            Self::Concat => "__concat",
+            Self::Div => "__div",
+            Self::Eq => "__eq",
+            Self::Gc => "__gc",
+            Self::Index => "__index",
+            Self::Le => "__le",
+            Self::Len => "__len",
+            Self::Lt => "__lt",
+            Self::Mod => "__mod",
+            Self::Mul => "__mul",
+            Self::NewIndex => "__newindex",
+            Self::Pow => "__pow",
+            Self::Sub => "__sub",
+            Self::ToString => "__tostring",
+            Self::Unm => "__unm",
+        }
+    }
+}
+
+impl fmt::Display for MetaMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Used to register the methods and metamethods of a [`UserData`].
+///
+/// Methods are called from Lua using the `:` syntax, e.g. `obj:method(a, b)`,
+/// and receive the rest of the arguments as a [`Poppable`]. The value is
+/// borrowed for the duration of the call, so calling a `_mut` method while
+/// the value is already borrowed results in an error instead of a panic.
+pub struct UserDataMethods<T> {
+    methods: Vec<(String, Callback)>,
+    meta_methods: Vec<(MetaMethod, Callback)>,
+    _marker: PhantomData<fn(T)>,
+}
+
+impl<T: UserData> UserDataMethods<T> {
+    fn new() -> Self {
+        Self {
+            methods: Vec::new(),
+            meta_methods: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds a method borrowing the value immutably.
+    pub fn add_method<F, A, R, E>(&mut self, name: &str, method: F)
+    where
+        F: Fn(&T, A) -> Result<R, E> + 'static,
+        A: Poppable,
+        R: Pushable,
+        E: StdError + 'static,
+    {
+        self.methods.push((name.to_owned(), callback(method)));
+    }
+
+    /// Adds a method borrowing the value mutably.
+    pub fn add_method_mut<F, A, R, E>(&mut self, name: &str, method: F)
+    where
+        F: Fn(&mut T, A) -> Result<R, E> + 'static,
+        A: Poppable,
+        R: Pushable,
+        E: StdError + 'static,
+    {
+        self.methods.push((name.to_owned(), callback_mut(method)));
+    }
+
+    /// Adds a metamethod borrowing the value immutably. For binary operators
+    /// the value is always the left operand.
+    ///
+    /// An [`Index`](MetaMethod::Index) metamethod is only called for keys
+    /// that aren't the name of a method, and a [`Gc`](MetaMethod::Gc)
+    /// metamethod is called right before the value is dropped.
+    pub fn add_meta_method<F, A, R, E>(&mut self, meta: MetaMethod, method: F)
+    where
+        F: Fn(&T, A) -> Result<R, E> + 'static,
+        A: Poppable,
+        R: Pushable,
+        E: StdError + 'static,
+    {
+        self.meta_methods.push((meta, callback(method)));
+    }
+
+    /// Adds a metamethod borrowing the value mutably, e.g. a
+    /// [`NewIndex`](MetaMethod::NewIndex).
+    pub fn add_meta_method_mut<F, A, R, E>(
+        &mut self,
+        meta: MetaMethod,
+        method: F,
+    ) where
+        F: Fn(&mut T, A) -> Result<R, E> + 'static,
+        A: Poppable,
+        R: Pushable,
+        E: StdError + 'static,
+    {
+        self.meta_methods.push((meta, callback_mut(method)));
+    }
+}
+
+/// Turns a method borrowing the value immutably into a [`Callback`].
+fn callback<T, F, A, R, E>(method: F) -> Callback
+where
+    T: UserData,
+    F: Fn(&T, A) -> Result<R, E> + 'static,
+    A: Poppable,
+    R: Pushable,
+    E: StdError + 'static,
+{
+    Box::new(move |lstate| unsafe {
+        let (cell, args) = pop_self_and_args::<T, A>(lstate)?;
+        let this = cell.try_borrow().map_err(borrow_error::<T, _>)?;
+        let ret =
+            method(&this, args).map_err(Error::push_error_from_err::<R, _>)?;
+        drop(this);
+        ret.push(lstate)
+    })
+}
+
+/// Turns a method borrowing the value mutably into a [`Callback`].
+fn callback_mut<T, F, A, R, E>(method: F) -> Callback
+where
+    T: UserData,
+    F: Fn(&mut T, A) -> Result<R, E> + 'static,
+    A: Poppable,
+    R: Pushable,
+    E: StdError + 'static,
+{
+    Box::new(move |lstate| unsafe {
+        let (cell, args) = pop_self_and_args::<T, A>(lstate)?;
+        let mut this = cell.try_borrow_mut().map_err(borrow_error::<T, _>)?;
+        let ret = method(&mut this, args)
+            .map_err(Error::push_error_from_err::<R, _>)?;
+        drop(this);
+        ret.push(lstate)
+    })
+}
+
+/// Pops the arguments a method was called with, checking that the first one
+/// is a userdata holding a `T`.
+unsafe fn pop_self_and_args<'a, T: UserData, A: Poppable>(
+    lstate: *mut lua_State,
+) -> Result<(&'a RefCell<T>, A), Error> {
+    let block = to_block::<T>(lstate, 1).ok_or_else(|| {
+        Error::pop_error(
+            type_name::<T>(),
+            "the first argument of a method has to be the userdata itself, \
+             did you call it with `.` instead of `:`?",
+        )
+    })?;
+
+    let cell = to_cell(block)?;
+
+    let args = A::pop_many(lstate, lua_gettop(lstate) - 1)?;
+
+    Ok((cell, args))
+}
+
+fn borrow_error<T, E: StdError>(err: E) -> Error {
+    Error::BorrowError { ty: type_name::<T>(), message: err.to_string() }
+}
+
+/// The memory block of a userdata holding a `T`. The value is boxed so that
+/// it's properly aligned no matter its type, and the pointer is set to null
+/// once the value has been dropped.
+type Block<T> = *mut RefCell<T>;
+
+/// Returns the memory block of the userdata at `idx`, or `None` if that's not
+/// a userdata holding a `T`.
+///
+/// NOTE: the block is only valid for as long as the userdata is alive.
+unsafe fn to_block<T: UserData>(
+    lstate: *mut lua_State,
+    idx: c_int,
+) -> Option<*mut Block<T>> {
+    if lua_type(lstate, idx) != LUA_TUSERDATA
+        || lua_getmetatable(lstate, idx) == 0
+    {
+        return None;
+    }
+
+    push_metatable::<T>(lstate);
+    let is_t = lua_rawequal(lstate, -1, -2) == 1;
+    lua_pop(lstate, 2);
+
+    is_t.then(|| lua_touserdata(lstate, idx) as *mut Block<T>)
+}
+
+/// Returns the value held by a memory block, or an error if it's already been
+/// dropped by the `__gc` metamethod.
+///
+/// NOTE: the value is only valid for as long as the userdata is alive.
+unsafe fn to_cell<'a, T>(
+    block: *mut Block<T>,
+) -> Result<&'a RefCell<T>, Error> {
+    match (*block).as_ref() {
+        Some(cell) => Ok(cell),
+        None => Err(Error::BorrowError {
+            ty: type_name::<T>(),
+            message: "the value has already been dropped".into(),
+        }),
+    }
+}
+
+/// Pushes the metatable shared by all the userdata holding a `T`, creating it
+/// the first time it's needed.
+unsafe fn push_metatable<T: UserData>(lstate: *mut lua_State) {
+    thread_local! {
+        static METATABLES: RefCell<HashMap<TypeId, c_int>> =
+            RefCell::new(HashMap::new());
+    }
+
+    let cached =
+        METATABLES.with(|m| m.borrow().get(&TypeId::of::<T>()).copied());
+
+    if let Some(lua_ref) = cached {
+        lua_rawgeti(lstate, LUA_REGISTRYINDEX, lua_ref);
+        return;
+    }
+
+    let mut methods = UserDataMethods::<T>::new();
+    T::add_methods(&mut methods);
+
+    let UserDataMethods { methods, meta_methods, .. } = methods;
+
+    lua_createtable(lstate, 0, meta_methods.len() as _);
+
+    let mut index = None;
+    let mut gc = None;
+
+    for (meta, callback) in meta_methods {
+        match meta {
+            MetaMethod::Index => index = Some(callback),
+            MetaMethod::Gc => gc = Some(callback),
+            _ => {
+                push_str(lstate, meta.name());
+                function::push_callback(lstate, callback);
+                lua_rawset(lstate, -3);
+            },
+        }
+    }
+
+    // Methods are looked up first, falling back to the `__index` metamethod
+    // if there's one.
+    push_str(lstate, MetaMethod::Index.name());
+
+    match (methods.is_empty(), index) {
+        (true, None) => lua_pushnil(lstate),
+
+        (true, Some(index)) => function::push_callback(lstate, index),
+
+        (false, index) => {
+            lua_createtable(lstate, 0, methods.len() as _);
+            for (name, callback) in methods {
+                push_str(lstate, &name);
+                function::push_callback(lstate, callback);
+                lua_rawset(lstate, -3);
+            }
+
+            if let Some(index) = index {
+                function::push_callback(lstate, index);
+                lua_pushcclosure(lstate, index_cb, 2);
+            }
+        },
+    }
+
+    lua_rawset(lstate, -3);
+
+    push_str(lstate, MetaMethod::Gc.name());
+    match gc {
+        Some(gc) => function::push_callback(lstate, gc),
+        None => lua_pushnil(lstate),
+    }
+    lua_pushcclosure(lstate, gc_cb::<T>, 1);
+    lua_rawset(lstate, -3);
+
+    // Hides the metatable from `getmetatable`, so that Lua code can't call
+    // the metamethods with arbitrary arguments.
+    push_str(lstate, "__metatable");
+    lua_pushboolean(lstate, 0);
+    lua_rawset(lstate, -3);
+
+    lua_pushvalue(lstate, -1);
+    let lua_ref = luaL_ref(lstate, LUA_REGISTRYINDEX);
+    METATABLES.with(|m| m.borrow_mut().insert(TypeId::of::<T>(), lua_ref));
+}
+
+/// The `__index` metamethod used when a type has both methods and an `Index`
+/// metamethod. The first upvalue is the table of methods, the second one is
+/// the metamethod.
+unsafe extern "C" fn index_cb(lstate: *mut lua_State) -> c_int {
+    lua_pushvalue(lstate, 2);
+    lua_rawget(lstate, lua_upvalueindex(1));
+
+    if lua_type(lstate, -1) != LUA_TNIL {
+        return 1;
+    }
+
+    lua_pop(lstate, 1);
+    lua_pushvalue(lstate, lua_upvalueindex(2));
+    lua_insert(lstate, 1);
+    lua_call(lstate, 2, 1);
+    1
+}
+
+/// The `__gc` metamethod of every userdata holding a `T`, which calls the
+/// `Gc` metamethod stored in the first upvalue (if any) and then drops the
+/// value.
+///
+/// The metamethod can still be reached via `debug.getmetatable`, so it has to
+/// check its argument and be a no-op when called more than once. Calling it
+/// manually while the value is borrowed raises an error instead of leaving
+/// the borrow dangling.
+unsafe extern "C" fn gc_cb<T: UserData>(lstate: *mut lua_State) -> c_int {
+    let block = match to_block::<T>(lstate, 1) {
+        Some(block) if !(*block).is_null() => block,
+        _ => return 0,
+    };
+
+    // A userdata can only be borrowed while it's reachable, so this is never
+    // the case when it's actually being collected.
+    if (**block).try_borrow_mut().is_err() {
+        crate::utils::raise(
+            lstate,
+            "cannot drop a userdata while its value is borrowed",
+        );
+    }
+
+    if lua_type(lstate, lua_upvalueindex(1)) != LUA_TNIL {
+        lua_pushvalue(lstate, lua_upvalueindex(1));
+        lua_pushvalue(lstate, 1);
+
+        // There's nowhere to report an error raised while collecting garbage.
+        if lua_pcall(lstate, 1, 0, 0) != LUA_OK {
+            lua_pop(lstate, 1);
+        }
+    }
+
+    // The pointer is cleared before dropping the value so that neither this
+    // function nor the `UserDataRef`s still pointing to the userdata can
+    // access it afterwards.
+    let cell = std::ptr::replace(block, std::ptr::null_mut());
+    let _ = crate::panic::catch(|| drop(Box::from_raw(cell)));
+
+    0
+}
+
+#[inline]
+unsafe fn push_str(lstate: *mut lua_State, s: &str) {
+    lua_pushlstring(lstate, s.as_ptr() as *const _, s.len());
+}
+
+/// A reference to a userdata holding a value of type `T`, which keeps it
+/// alive for as long as it exists.
+///
+/// The value can be borrowed both from Rust and from the methods called by
+/// Lua. Borrows are checked at runtime like with a [`RefCell`], returning an
+/// error if the value is already mutably borrowed.
+pub struct UserDataRef<T> {
+    inner: RegistryRef,
+    block: *mut Block<T>,
+}
+
+impl<T: UserData> UserDataRef<T> {
+    /// Moves `value` into a new userdata.
+    pub fn new(value: T) -> Self {
+        unsafe {
+            crate::with_state(|lstate| {
+                let block =
+                    lua_newuserdata(lstate, std::mem::size_of::<Block<T>>())
+                        as *mut Block<T>;
+                let cell = Box::into_raw(Box::new(RefCell::new(value)));
+                std::ptr::write(block, cell);
+
+                push_metatable::<T>(lstate);
+                lua_setmetatable(lstate, -2);
+
+                Self { inner: RegistryRef::pop(lstate), block }
+            })
+        }
+    }
+
+    /// Immutably borrows the value.
+    ///
+    /// Returns an error if the value is already mutably borrowed, or if it's
+    /// been dropped by calling the `__gc` metamethod manually.
+    pub fn borrow(&self) -> Result<Ref<'_, T>, Error> {
+        unsafe { to_cell(self.block)? }
+            .try_borrow()
+            .map_err(borrow_error::<T, _>)
+    }
+
+    /// Mutably borrows the value.
+    ///
+    /// Returns an error if the value is already borrowed, or if it's been
+    /// dropped by calling the `__gc` metamethod manually.
+    pub fn borrow_mut(&self) -> Result<RefMut<'_, T>, Error> {
+        unsafe { to_cell(self.block)? }
+            .try_borrow_mut()
+            .map_err(borrow_error::<T, _>)
+    }
+}
+
+impl<T> Clone for UserDataRef<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), block: self.block }
+    }
+}
+
+impl<T> PartialEq for UserDataRef<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.block == other.block
+    }
+}
+
+impl<T> fmt::Debug for UserDataRef<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "userdata<{}>: {:p}", type_name::<T>(), self.inner.as_ptr())
+    }
+}
+
+impl<T: UserData> From<UserDataRef<T>> for LuaUserData {
+    fn from(userdata: UserDataRef<T>) -> Self {
+        Self::from_ref(userdata.inner)
+    }
+}
+
+impl<T: UserData> Pushable for UserDataRef<T> {
+    unsafe fn push(self, lstate: *mut lua_State) -> Result<c_int, Error> {
+        self.inner.push(lstate);
+        Ok(1)
+    }
+}
+
+impl<T: UserData> Poppable for UserDataRef<T> {
+    unsafe fn pop(lstate: *mut lua_State) -> Result<Self, Error> {
+        if lua_gettop(lstate) == 0 {
+            return Err(Error::PopEmptyStack);
+        }
+
+        match to_block::<T>(lstate, -1) {
+            Some(block) => Ok(Self { inner: RegistryRef::pop(lstate), block }),
+
+            None => Err(Error::pop_error(
+                type_name::<Self>(),
+                format!(
+                    "expected a userdata holding a {}, found {}",
+                    type_name::<T>(),
+                    crate::utils::debug_type(lstate, -1)
+                ),
+            )),
+        }
+    }
+}
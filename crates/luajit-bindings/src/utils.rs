@@ -77,7 +77,11 @@ pub unsafe fn handle_error<E: std::error::Error + ?Sized>(
     lstate: *mut lua_State,
     err: &E,
 ) -> ! {
-    let msg = err.to_string();
+    raise(lstate, &err.to_string())
+}
+
+/// Raises a Lua error with the given message.
+pub unsafe fn raise(lstate: *mut lua_State, msg: &str) -> ! {
     ffi::lua_pushlstring(lstate, msg.as_ptr() as *const _, msg.len());
     ffi::lua_error(lstate);
 }
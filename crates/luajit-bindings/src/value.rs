@@ -0,0 +1,222 @@
+use std::ffi::{c_int, c_void};
+
+use crate::ffi::*;
+use crate::{utils, Error, LuaFunction, LuaTable, LuaThread, LuaUserData};
+use crate::{Poppable, Pushable};
+
+/// A dynamically typed Lua value.
+///
+/// Tables, functions and userdata are stored in the registry and are kept
+/// alive for as long as the value exists.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum LuaValue {
+    #[default]
+    Nil,
+    Boolean(bool),
+    Number(f64),
+
+    /// Lua strings are arbitrary byte sequences, not necessarily valid UTF-8.
+    String(Vec<u8>),
+
+    Table(LuaTable),
+    Function(LuaFunction),
+    UserData(LuaUserData),
+    LightUserData(*mut c_void),
+    Thread(LuaThread),
+}
+
+impl LuaValue {
+    /// Returns the name of the Lua type of the value, e.g. `"table"`.
+    pub fn type_name(&self) -> &'static str {
+        utils::type_name(self.lua_type())
+    }
+
+    #[inline]
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Self::Nil)
+    }
+
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Returns the string if the value is a string containing valid UTF-8.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(bytes) => std::str::from_utf8(bytes).ok(),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_table(&self) -> Option<&LuaTable> {
+        match self {
+            Self::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_function(&self) -> Option<&LuaFunction> {
+        match self {
+            Self::Function(fun) => Some(fun),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_userdata(&self) -> Option<&LuaUserData> {
+        match self {
+            Self::UserData(ud) => Some(ud),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_thread(&self) -> Option<&LuaThread> {
+        match self {
+            Self::Thread(thread) => Some(thread),
+            _ => None,
+        }
+    }
+
+    fn lua_type(&self) -> c_int {
+        match self {
+            Self::Nil => LUA_TNIL,
+            Self::Boolean(_) => LUA_TBOOLEAN,
+            Self::Number(_) => LUA_TNUMBER,
+            Self::String(_) => LUA_TSTRING,
+            Self::Table(_) => LUA_TTABLE,
+            Self::Function(_) => LUA_TFUNCTION,
+            Self::UserData(_) => LUA_TUSERDATA,
+            Self::LightUserData(_) => LUA_TLIGHTUSERDATA,
+            Self::Thread(_) => LUA_TTHREAD,
+        }
+    }
+
+    /// Pushes the value on the stack without consuming it.
+    pub(crate) unsafe fn push_ref(&self, lstate: *mut lua_State) {
+        match self {
+            Self::Nil => lua_pushnil(lstate),
+            Self::Boolean(b) => lua_pushboolean(lstate, *b as _),
+            Self::Number(n) => lua_pushnumber(lstate, *n),
+            Self::String(bytes) => lua_pushlstring(
+                lstate,
+                bytes.as_ptr() as *const _,
+                bytes.len(),
+            ),
+            Self::Table(table) => table.as_ref().push(lstate),
+            Self::Function(fun) => fun.as_ref().push(lstate),
+            Self::UserData(ud) => ud.as_ref().push(lstate),
+            Self::LightUserData(ptr) => lua_pushlightuserdata(lstate, *ptr),
+            Self::Thread(thread) => thread.as_ref().push(lstate),
+        }
+    }
+}
+
+impl Pushable for LuaValue {
+    unsafe fn push(self, lstate: *mut lua_State) -> Result<c_int, Error> {
+        self.push_ref(lstate);
+        Ok(1)
+    }
+}
+
+impl Poppable for LuaValue {
+    unsafe fn pop(lstate: *mut lua_State) -> Result<Self, Error> {
+        if lua_gettop(lstate) == 0 {
+            return Err(Error::PopEmptyStack);
+        }
+
+        let value = match lua_type(lstate, -1) {
+            LUA_TNIL => Self::Nil,
+
+            LUA_TBOOLEAN => Self::Boolean(lua_toboolean(lstate, -1) == 1),
+
+            LUA_TNUMBER => Self::Number(lua_tonumber(lstate, -1)),
+
+            LUA_TSTRING => {
+                let mut len = 0;
+                let ptr = lua_tolstring(lstate, -1, &mut len);
+                let bytes = std::slice::from_raw_parts(ptr as *const u8, len);
+                Self::String(bytes.to_owned())
+            },
+
+            LUA_TLIGHTUSERDATA => {
+                Self::LightUserData(lua_touserdata(lstate, -1))
+            },
+
+            // These are popped by `luaL_ref`.
+            LUA_TTABLE => return LuaTable::pop(lstate).map(Self::Table),
+            LUA_TFUNCTION => {
+                return LuaFunction::pop(lstate).map(Self::Function)
+            },
+            LUA_TUSERDATA => {
+                return LuaUserData::pop(lstate).map(Self::UserData)
+            },
+            LUA_TTHREAD => return LuaThread::pop(lstate).map(Self::Thread),
+
+            other => {
+                return Err(Error::pop_error(
+                    std::any::type_name::<Self>(),
+                    format!(
+                        "values of type {} aren't supported",
+                        utils::type_name(other)
+                    ),
+                ))
+            },
+        };
+
+        lua_pop(lstate, 1);
+
+        Ok(value)
+    }
+}
+
+macro_rules! from_variant {
+    ($type:ty, $variant:ident) => {
+        impl From<$type> for LuaValue {
+            fn from(value: $type) -> Self {
+                Self::$variant(value)
+            }
+        }
+    };
+}
+
+from_variant!(bool, Boolean);
+from_variant!(f64, Number);
+from_variant!(Vec<u8>, String);
+from_variant!(LuaTable, Table);
+from_variant!(LuaFunction, Function);
+from_variant!(LuaUserData, UserData);
+from_variant!(LuaThread, Thread);
+
+impl From<()> for LuaValue {
+    fn from(_: ()) -> Self {
+        Self::Nil
+    }
+}
+
+impl From<String> for LuaValue {
+    fn from(s: String) -> Self {
+        Self::String(s.into_bytes())
+    }
+}
+
+impl From<&str> for LuaValue {
+    fn from(s: &str) -> Self {
+        Self::String(s.as_bytes().to_owned())
+    }
+}
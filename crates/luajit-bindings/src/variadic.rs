@@ -0,0 +1,138 @@
+use std::ffi::c_int;
+use std::iter::FromIterator;
+use std::ops::{Deref, DerefMut};
+
+use crate::ffi::*;
+use crate::{Error, LuaValue, Poppable, Pushable};
+
+/// A variable number of values of the same type, used to mimic Lua's `...`.
+///
+/// When popped as the last element of a tuple it collects all the remaining
+/// values, so a Rust function taking `(String, Variadic<i32>)` as argument
+/// behaves like `function(s, ...)`. When pushed every element becomes a
+/// separate value, e.g. a separate return value of a function.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::{lua::Variadic, Function};
+///
+/// let sum = Function::from_fn(|nums: Variadic<i32>| {
+///     Ok::<_, nvim_oxi::Error>(nums.iter().sum::<i32>())
+/// });
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Variadic<T>(Vec<T>);
+
+/// A variable number of values of any type, e.g. all the values returned by
+/// a Lua function.
+pub type MultiValue = Variadic<LuaValue>;
+
+impl<T> Variadic<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Consumes the `Variadic`, returning the underlying vector.
+    #[inline]
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for Variadic<T> {
+    type Target = Vec<T>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Variadic<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<Vec<T>> for Variadic<T> {
+    #[inline]
+    fn from(vec: Vec<T>) -> Self {
+        Self(vec)
+    }
+}
+
+impl<T> From<Variadic<T>> for Vec<T> {
+    #[inline]
+    fn from(variadic: Variadic<T>) -> Self {
+        variadic.0
+    }
+}
+
+impl<T> FromIterator<T> for Variadic<T> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<T> IntoIterator for Variadic<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Variadic<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: Pushable> Pushable for Variadic<T> {
+    unsafe fn push(self, lstate: *mut lua_State) -> Result<c_int, Error> {
+        if lua_checkstack(lstate, self.len() as _) == 0 {
+            return Err(Error::push_error(
+                std::any::type_name::<Self>(),
+                "too many values to push on the stack",
+            ));
+        }
+
+        let mut pushed = 0;
+        for value in self {
+            pushed += value.push(lstate)?;
+        }
+        Ok(pushed)
+    }
+}
+
+impl<T: Poppable> Poppable for Variadic<T> {
+    /// Pops a single value, e.g. when the `Variadic` is an element of a
+    /// table. Only [`pop_many`](Poppable::pop_many) takes more than one.
+    unsafe fn pop(lstate: *mut lua_State) -> Result<Self, Error> {
+        T::pop(lstate).map(|value| Self(vec![value]))
+    }
+
+    unsafe fn pop_many(
+        lstate: *mut lua_State,
+        n: c_int,
+    ) -> Result<Self, Error> {
+        // The values are popped starting from the last one.
+        let mut values = (0..n)
+            .map(|_| T::pop_many(lstate, 1))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        values.reverse();
+
+        Ok(Self(values))
+    }
+}
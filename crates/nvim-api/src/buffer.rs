@@ -28,6 +28,9 @@ use crate::{Error, Result};
 
 /// A wrapper around a Neovim buffer handle.
 #[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+// Must match `nvim_types::serde::BUFFER_NEWTYPE`, which lets the serializers
+// tell handles apart from plain integers.
+#[serde(rename = "$__nvim_oxi_Buffer")]
 pub struct Buffer(pub(crate) BufHandle);
 
 impl fmt::Debug for Buffer {
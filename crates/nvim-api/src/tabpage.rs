@@ -18,6 +18,9 @@ use crate::Window;
 
 /// A wrapper around a Neovim tab handle.
 #[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+// Must match `nvim_types::serde::TABPAGE_NEWTYPE`, which lets the serializers
+// tell handles apart from plain integers.
+#[serde(rename = "$__nvim_oxi_TabPage")]
 pub struct TabPage(pub(crate) TabHandle);
 
 impl fmt::Debug for TabPage {
@@ -21,6 +21,9 @@ use crate::{Buffer, TabPage};
 
 /// A wrapper around a Neovim window handle.
 #[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+// Must match `nvim_types::serde::WINDOW_NEWTYPE`, which lets the serializers
+// tell handles apart from plain integers.
+#[serde(rename = "$__nvim_oxi_Window")]
 pub struct Window(pub(crate) WinHandle);
 
 impl fmt::Debug for Window {
@@ -7,7 +7,7 @@ use luajit_bindings::{self as lua, ffi::lua_State, Pushable};
 /// The entrypoint of the plugin.
 ///
 /// Initializes the Lua state, executes the entrypoint function and pushes the
-/// result on the stack.
+/// result on the stack. Errors and panics are turned into Lua errors.
 #[doc(hidden)]
 pub unsafe fn entrypoint<R, E>(
     lua_state: *mut lua_State,
@@ -22,8 +22,12 @@ where
     #[cfg(feature = "libuv")]
     libuv_bindings::init(lua_state);
 
-    match body() {
-        Ok(api) => api.push(lua_state).unwrap(),
-        Err(err) => lua::utils::handle_error(lua_state, &err),
+    // The Lua error has to be raised after the panic has been caught, or
+    // `lua_error` would unwind through `catch`.
+    match lua::panic::catch(|| body().map(|api| api.push(lua_state))) {
+        Ok(Ok(Ok(num_pushed))) => num_pushed,
+        Ok(Ok(Err(err))) => lua::utils::handle_error(lua_state, &err),
+        Ok(Err(err)) => lua::utils::handle_error(lua_state, &err),
+        Err(panic) => lua::utils::raise(lua_state, &panic.to_string()),
     }
 }
@@ -23,6 +23,14 @@ pub mod api {
     pub use nvim_api::*;
 }
 
+pub mod conversion {
+    //! Traits for converting between Neovim [`Object`](crate::Object)s and
+    //! Rust types.
+    #[doc(inline)]
+    pub use nvim_types::conversion::*;
+    pub use oxi_derive::{FromObject, ToObject};
+}
+
 #[cfg(feature = "libuv")]
 #[cfg_attr(docsrs, doc(cfg(feature = "libuv")))]
 pub mod libuv {
@@ -40,6 +48,7 @@ pub mod lua {
     //! [LuaJIT]: https://luajit.org/
     #[doc(inline)]
     pub use luajit_bindings::*;
+    pub use oxi_derive::{Poppable, Pushable};
 }
 
 #[cfg(feature = "mlua")]
@@ -86,6 +95,12 @@ pub mod diagnostic {
     pub use nvim_diagnostic::*;
 }
 
+#[doc(hidden)]
+pub mod __private {
+    //! Used by the code generated by the derive macros.
+    pub use serde;
+}
+
 #[doc(hidden)]
 pub use entrypoint::entrypoint;
 pub use error::{Error, Result};
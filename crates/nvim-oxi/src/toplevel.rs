@@ -1,4 +1,4 @@
-use luajit_bindings::{self as lua, ffi::*, macros::cstr};
+use luajit_bindings as lua;
 use nvim_types::Function;
 
 use crate::Result;
@@ -18,22 +18,13 @@ where
     //
     // Unfortunately the `nlua_schedule` C function is not exported, so we have
     // to call the Lua function instead.
-    unsafe {
-        lua::with_state(move |lstate| {
-            // Put `vim.schedule` on the stack.
-            lua_getglobal(lstate, cstr!("vim"));
-            lua_getfield(lstate, -1, cstr!("schedule"));
+    let fun = Function::from_fn_once(fun);
+    let lua_ref = fun.lua_ref();
 
-            // Store the function in the registry and put a reference to it on
-            // the stack.
-            let fun = Function::from_fn_once(fun);
-            lua_rawgeti(lstate, LUA_REGISTRYINDEX, fun.lua_ref());
+    // `vim.schedule` only fails if it's not passed a function.
+    let _ = lua::call_path::<_, ()>("vim.schedule", fun);
 
-            lua_call(lstate, 1, 0);
-
-            // Pop `vim` off the stack and remove the function from the registry.
-            lua_pop(lstate, 1);
-            luaL_unref(lstate, LUA_REGISTRYINDEX, fun.lua_ref());
-        })
-    };
+    // `vim.schedule` holds its own reference to the function, so we can remove
+    // ours from the registry.
+    lua::function::remove(lua_ref);
 }
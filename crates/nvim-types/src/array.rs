@@ -24,6 +24,45 @@ impl Display for Array {
     }
 }
 
+impl Array {
+    /// Appends an element to the back of the array.
+    #[inline]
+    pub fn push<V: Into<Object>>(&mut self, value: V) {
+        let value = value.into();
+        self.with_vec(|vec| vec.push(value))
+    }
+
+    /// Removes the last element from the array and returns it, or `None` if
+    /// it's empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<Object> {
+        self.with_vec(Vec::pop)
+    }
+
+    /// Inserts an element at position `index`, shifting all the elements
+    /// after it to the right.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[inline]
+    pub fn insert<V: Into<Object>>(&mut self, index: usize, value: V) {
+        let value = value.into();
+        self.with_vec(|vec| vec.insert(index, value))
+    }
+
+    /// Removes and returns the element at position `index`, shifting all the
+    /// elements after it to the left.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn remove(&mut self, index: usize) -> Object {
+        self.with_vec(|vec| vec.remove(index))
+    }
+}
+
 impl lua::Pushable for Array {
     unsafe fn push(self, state: *mut lua_State) -> Result<c_int, lua::Error> {
         <Vec<Object>>::from(self).push(state)
@@ -50,6 +89,8 @@ impl IntoIterator for Array {
     }
 }
 
+/// Collecting into an [`Array`] skips the `nil` objects, just like
+/// [`extend`](Array::extend) does. Use [`push`](Array::push) to add a `nil`.
 impl<T> FromIterator<T> for Array
 where
     T: Into<Object>,
@@ -63,6 +104,20 @@ where
     }
 }
 
+/// Skips the `nil` objects, like collecting into an [`Array`] does.
+impl<T> Extend<T> for Array
+where
+    T: Into<Object>,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.with_vec(|vec| {
+            vec.extend(
+                iter.into_iter().map(Into::into).filter(Object::is_some),
+            )
+        })
+    }
+}
+
 /// An owning iterator over the [`Object`]s of a Neovim [`Array`].
 pub struct ArrayIterator {
     start: *const Object,
@@ -195,4 +250,52 @@ mod tests {
         let arr = Array::from_iter([Array::from((1, 2, 3))]);
         assert_eq!(String::from("[[1, 2, 3]]"), format!("{arr}"));
     }
+
+    #[test]
+    fn push_pop_insert_remove() {
+        let mut arr = Array::new();
+        arr.push("foo");
+        arr.push(42);
+        arr.insert(1, true);
+        assert_eq!(Array::from(("foo", true, 42)), arr);
+
+        assert_eq!(Object::from(true), arr.remove(1));
+        assert_eq!(Some(Object::from(42)), arr.pop());
+        assert_eq!(Some(Object::from("foo")), arr.pop());
+        assert_eq!(None, arr.pop());
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn extend_and_slice() {
+        let mut arr = Array::from_iter(["a"]);
+        arr.extend(["b", "c"]);
+        assert_eq!(3, arr.len());
+        assert_eq!([Object::from("b"), Object::from("c")], arr[1..]);
+
+        for obj in arr.iter_mut() {
+            *obj = Object::from(0);
+        }
+        assert_eq!(Array::from((0, 0, 0)), arr);
+
+        arr.clear();
+        assert!(arr.is_empty());
+    }
+
+    #[test]
+    fn extend_and_collect_skip_nils() {
+        let objs = || [Object::from(1), Object::nil(), Object::from(2)];
+
+        let mut arr = Array::new();
+        arr.extend(objs());
+        assert_eq!(Array::from_iter(objs()), arr);
+        assert_eq!(Array::from((1, 2)), arr);
+    }
+
+    #[test]
+    fn into_vec_doesnt_drop_items() {
+        let arr = Array::from_iter(["foo", "bar"]);
+        let vec = Vec::from(arr);
+        assert_eq!(vec![Object::from("foo"), Object::from("bar")], vec);
+    }
 }
@@ -22,6 +22,26 @@ pub struct KeyValuePair {
     pub(crate) value: Object,
 }
 
+impl KeyValuePair {
+    /// Returns a reference to the key of this pair.
+    #[inline]
+    pub fn key(&self) -> &String {
+        &self.key
+    }
+
+    /// Returns a reference to the value of this pair.
+    #[inline]
+    pub fn value(&self) -> &Object {
+        &self.value
+    }
+
+    /// Returns a mutable reference to the value of this pair.
+    #[inline]
+    pub fn value_mut(&mut self) -> &mut Object {
+        &mut self.value
+    }
+}
+
 impl fmt::Debug for KeyValuePair {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Display::fmt(self, f)
@@ -60,6 +80,199 @@ impl Dictionary {
         self.iter_mut()
             .find_map(|pair| (&pair.key == query).then_some(&mut pair.value))
     }
+
+    /// Returns `true` if the dictionary contains a value for the given key.
+    #[inline]
+    pub fn contains_key<Q>(&self, query: &Q) -> bool
+    where
+        String: PartialEq<Q>,
+    {
+        self.position(query).is_some()
+    }
+
+    /// Inserts a key-value pair into the dictionary, returning the value
+    /// previously associated to the key, if any.
+    ///
+    /// New keys are appended at the end, while the position of existing keys
+    /// is preserved.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<Object>
+    where
+        K: Into<String>,
+        V: Into<Object>,
+    {
+        let key = key.into();
+        let value = value.into();
+
+        match self.get_mut(&key) {
+            Some(old) => Some(std::mem::replace(old, value)),
+            None => {
+                self.with_vec(|vec| vec.push(KeyValuePair { key, value }));
+                None
+            },
+        }
+    }
+
+    /// Removes a key from the dictionary, returning its value if the key was
+    /// present. The order of the remaining pairs is preserved.
+    pub fn remove<Q>(&mut self, query: &Q) -> Option<Object>
+    where
+        String: PartialEq<Q>,
+    {
+        let idx = self.position(query)?;
+        Some(self.with_vec(|vec| vec.remove(idx)).value)
+    }
+
+    /// Returns the entry of the given key for in-place manipulation.
+    pub fn entry<K: Into<String>>(&mut self, key: K) -> DictEntry<'_> {
+        let key = key.into();
+
+        match self.position(&key) {
+            Some(idx) => {
+                DictEntry::Occupied(OccupiedEntry { dict: self, idx })
+            },
+            None => DictEntry::Vacant(VacantEntry { dict: self, key }),
+        }
+    }
+
+    /// An iterator over the keys of the dictionary.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &String> + '_ {
+        self.iter().map(KeyValuePair::key)
+    }
+
+    /// An iterator over the values of the dictionary.
+    #[inline]
+    pub fn values(&self) -> impl Iterator<Item = &Object> + '_ {
+        self.iter().map(KeyValuePair::value)
+    }
+
+    /// An iterator over mutable references to the values of the dictionary.
+    #[inline]
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Object> + '_ {
+        self.iter_mut().map(KeyValuePair::value_mut)
+    }
+
+    #[inline]
+    fn position<Q>(&self, query: &Q) -> Option<usize>
+    where
+        String: PartialEq<Q>,
+    {
+        self.iter().position(|pair| &pair.key == query)
+    }
+}
+
+/// A view into a single entry of a [`Dictionary`], obtained via
+/// [`Dictionary::entry`].
+pub enum DictEntry<'a> {
+    Occupied(OccupiedEntry<'a>),
+    Vacant(VacantEntry<'a>),
+}
+
+/// An entry whose key is present in the [`Dictionary`].
+pub struct OccupiedEntry<'a> {
+    dict: &'a mut Dictionary,
+    idx: usize,
+}
+
+/// An entry whose key is not present in the [`Dictionary`].
+pub struct VacantEntry<'a> {
+    dict: &'a mut Dictionary,
+    key: String,
+}
+
+impl<'a> DictEntry<'a> {
+    /// The key of the entry.
+    #[inline]
+    pub fn key(&self) -> &String {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, then returns a mutable
+    /// reference to the value.
+    #[inline]
+    pub fn or_insert<V: Into<Object>>(self, default: V) -> &'a mut Object {
+        self.or_insert_with(|| default)
+    }
+
+    /// Same as [`or_insert`](DictEntry::or_insert) but the default value is
+    /// only computed if the entry is vacant.
+    #[inline]
+    pub fn or_insert_with<F, V>(self, default: F) -> &'a mut Object
+    where
+        F: FnOnce() -> V,
+        V: Into<Object>,
+    {
+        match self {
+            Self::Occupied(entry) => entry.into_mut(),
+            Self::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls `fun` on the value if the entry is occupied.
+    #[inline]
+    pub fn and_modify<F: FnOnce(&mut Object)>(mut self, fun: F) -> Self {
+        if let Self::Occupied(entry) = &mut self {
+            fun(entry.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a> OccupiedEntry<'a> {
+    #[inline]
+    pub fn key(&self) -> &String {
+        &self.dict.as_slice()[self.idx].key
+    }
+
+    #[inline]
+    pub fn get(&self) -> &Object {
+        &self.dict.as_slice()[self.idx].value
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut Object {
+        &mut self.dict.as_mut_slice()[self.idx].value
+    }
+
+    /// Converts the entry into a mutable reference to its value which is
+    /// bound to the lifetime of the dictionary.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut Object {
+        &mut self.dict.as_mut_slice()[self.idx].value
+    }
+
+    /// Replaces the value of the entry, returning the old one.
+    #[inline]
+    pub fn insert<V: Into<Object>>(&mut self, value: V) -> Object {
+        std::mem::replace(self.get_mut(), value.into())
+    }
+
+    /// Removes the entry from the dictionary, returning its value.
+    #[inline]
+    pub fn remove(self) -> Object {
+        let idx = self.idx;
+        self.dict.with_vec(|vec| vec.remove(idx)).value
+    }
+}
+
+impl<'a> VacantEntry<'a> {
+    #[inline]
+    pub fn key(&self) -> &String {
+        &self.key
+    }
+
+    /// Inserts the value at the end of the dictionary, returning a mutable
+    /// reference to it.
+    #[inline]
+    pub fn insert<V: Into<Object>>(self, value: V) -> &'a mut Object {
+        let pair = KeyValuePair { key: self.key, value: value.into() };
+        self.dict.with_vec(|vec| vec.push(pair));
+        let idx = self.dict.len() - 1;
+        &mut self.dict.as_mut_slice()[idx].value
+    }
 }
 
 impl fmt::Debug for Dictionary {
@@ -206,6 +419,20 @@ where
     }
 }
 
+impl<K, V> Extend<(K, V)> for Dictionary
+where
+    K: Into<String>,
+    V: Into<Object>,
+{
+    /// Inserts every pair via [`Dictionary::insert`], so keys which are
+    /// already present have their values replaced.
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
 impl<K, V> From<HashMap<K, V>> for Dictionary
 where
     String: From<K>,
@@ -282,4 +509,63 @@ mod tests {
 
         assert_eq!(String::from("{foo: {a: 1}}"), format!("{dict}"));
     }
+
+    #[test]
+    fn insert_remove() {
+        let mut dict = Dictionary::new();
+        assert_eq!(None, dict.insert("foo", 1));
+        assert_eq!(None, dict.insert("bar", 2));
+        assert_eq!(Some(Object::from(1)), dict.insert("foo", 3));
+
+        assert!(dict.contains_key(&"foo"));
+        assert_eq!(
+            vec!["foo", "bar"],
+            dict.keys().map(|key| key.as_str().unwrap()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![&Object::from(3), &Object::from(2)],
+            dict.values().collect::<Vec<_>>()
+        );
+
+        assert_eq!(Some(Object::from(3)), dict.remove(&"foo"));
+        assert_eq!(None, dict.remove(&"foo"));
+        assert!(!dict.contains_key(&"foo"));
+        assert_eq!(1, dict.len());
+    }
+
+    #[test]
+    fn entry() {
+        let mut dict = Dictionary::from_iter([("foo", 1)]);
+
+        dict.entry("foo")
+            .and_modify(|obj| *obj = Object::from(2))
+            .or_insert(0);
+        dict.entry("bar").and_modify(|_| unreachable!()).or_insert("bar");
+
+        assert_eq!(
+            Dictionary::from_iter([
+                ("foo", Object::from(2)),
+                ("bar", Object::from("bar"))
+            ]),
+            dict
+        );
+
+        match dict.entry("foo") {
+            super::DictEntry::Occupied(entry) => {
+                assert_eq!(Object::from(2), entry.remove())
+            },
+            super::DictEntry::Vacant(_) => unreachable!(),
+        }
+        assert_eq!(Dictionary::from_iter([("bar", "bar")]), dict);
+    }
+
+    #[test]
+    fn extend_replaces_existing_keys() {
+        let mut dict = Dictionary::from_iter([("foo", 1), ("bar", 2)]);
+        dict.extend([("bar", 3), ("baz", 4)]);
+        assert_eq!(
+            Dictionary::from_iter([("foo", 1), ("bar", 3), ("baz", 4)]),
+            dict
+        );
+    }
 }
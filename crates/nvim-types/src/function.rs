@@ -150,10 +150,11 @@ impl<A, R> Function<A, R> {
 mod serde {
     use std::fmt;
 
-    use serde::de::{self, Deserialize, Deserializer, Visitor};
+    use serde::de::{Deserialize, Deserializer, Visitor};
     use serde::ser::{Serialize, Serializer};
 
     use super::Function;
+    use crate::serde::LUA_REF_NEWTYPE;
     use crate::LuaRef;
 
     impl<A, R> Serialize for Function<A, R> {
@@ -161,7 +162,7 @@ mod serde {
         where
             S: Serializer,
         {
-            serializer.serialize_f32(self.lua_ref as f32)
+            serializer.serialize_newtype_struct(LUA_REF_NEWTYPE, &self.lua_ref)
         }
     }
 
@@ -178,19 +179,24 @@ mod serde {
                 type Value = Function<A, R>;
 
                 fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                    f.write_str("an f32 representing a Lua reference")
+                    f.write_str("a reference to a Lua function")
                 }
 
-                fn visit_f32<E>(self, value: f32) -> Result<Self::Value, E>
+                fn visit_newtype_struct<D>(
+                    self,
+                    deserializer: D,
+                ) -> Result<Self::Value, D::Error>
                 where
-                    E: de::Error,
+                    D: Deserializer<'de>,
                 {
-                    Ok(Function::from_ref(value as LuaRef))
+                    LuaRef::deserialize(deserializer).map(Function::from_ref)
                 }
             }
 
-            deserializer
-                .deserialize_f32(FunctionVisitor(PhantomData, PhantomData))
+            deserializer.deserialize_newtype_struct(
+                LUA_REF_NEWTYPE,
+                FunctionVisitor(PhantomData, PhantomData),
+            )
         }
     }
 }
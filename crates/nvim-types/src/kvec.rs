@@ -1,6 +1,7 @@
 //! This module contains functionality common to both `Array`s and
 //! `Dictionary`s.
 
+use std::mem::{self, ManuallyDrop};
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 use std::slice;
@@ -58,7 +59,11 @@ impl<T> KVec<T> {
 
     #[inline]
     pub(crate) fn as_mut_slice(&mut self) -> &mut [T] {
-        unsafe { slice::from_raw_parts_mut(self.items, self.size) }
+        if self.items.is_null() {
+            &mut []
+        } else {
+            unsafe { slice::from_raw_parts_mut(self.items, self.size) }
+        }
     }
 
     #[inline]
@@ -70,6 +75,25 @@ impl<T> KVec<T> {
         Self { items: ptr, size, capacity }
     }
 
+    /// Removes all the items from the collection.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.with_vec(Vec::clear)
+    }
+
+    /// Temporarily turns the collection into a `Vec` to modify it, which
+    /// takes care of growing and shrinking the allocation.
+    #[inline]
+    pub(crate) fn with_vec<F, R>(&mut self, fun: F) -> R
+    where
+        F: FnOnce(&mut Vec<T>) -> R,
+    {
+        let mut vec = Vec::from(mem::take(self));
+        let ret = fun(&mut vec);
+        *self = vec.into();
+        ret
+    }
+
     /// Make a non-owning version of this `Collection`.
     #[inline]
     #[doc(hidden)]
@@ -129,6 +153,9 @@ impl<T> From<Vec<T>> for KVec<T> {
 impl<T> From<KVec<T>> for Vec<T> {
     #[inline]
     fn from(coll: KVec<T>) -> Self {
+        // The items are now owned by the `Vec`, so `coll`'s destructor must
+        // not run.
+        let coll = ManuallyDrop::new(coll);
         unsafe {
             if coll.items.is_null() {
                 Vec::new()
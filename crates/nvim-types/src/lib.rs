@@ -14,12 +14,19 @@ pub mod serde;
 mod string;
 
 pub use array::{Array, ArrayIterator};
-pub use dictionary::{DictIterator, Dictionary, KeyValuePair};
+pub use dictionary::{
+    DictEntry,
+    DictIterator,
+    Dictionary,
+    KeyValuePair,
+    OccupiedEntry,
+    VacantEntry,
+};
 pub use error::Error;
 pub use function::Function;
 pub use kvec::KVec;
 pub use non_owning::NonOwning;
-pub use object::{Object, ObjectKind};
+pub use object::{Object, ObjectKind, ObjectMut, ObjectRef};
 pub use string::String;
 
 // https://github.com/neovim/neovim/blob/master/src/nvim/api/private/defs.h#L67
@@ -77,6 +77,41 @@ union ObjectData {
     luaref: LuaRef,
 }
 
+/// A borrowed view into an [`Object`], obtained via [`Object::view`].
+///
+/// Unlike the `as_*_unchecked` methods this allows inspecting an object
+/// without any `unsafe` code by matching on its variants.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ObjectRef<'a> {
+    Nil,
+    Boolean(Boolean),
+    Integer(Integer),
+    Float(Float),
+    String(&'a crate::String),
+    Array(&'a Array),
+    Dictionary(&'a Dictionary),
+    LuaRef(LuaRef),
+    Buffer(Integer),
+    Window(Integer),
+    TabPage(Integer),
+}
+
+/// A mutable view into an [`Object`], obtained via [`Object::view_mut`].
+#[derive(Debug, PartialEq)]
+pub enum ObjectMut<'a> {
+    Nil,
+    Boolean(&'a mut Boolean),
+    Integer(&'a mut Integer),
+    Float(&'a mut Float),
+    String(&'a mut crate::String),
+    Array(&'a mut Array),
+    Dictionary(&'a mut Dictionary),
+    LuaRef(&'a mut LuaRef),
+    Buffer(&'a mut Integer),
+    Window(&'a mut Integer),
+    TabPage(&'a mut Integer),
+}
+
 impl Default for Object {
     fn default() -> Object {
         Object::nil()
@@ -129,11 +164,170 @@ impl Object {
         Self { ty: ObjectKind::LuaRef, data: ObjectData { luaref } }
     }
 
+    /// Creates an object of the given kind holding an integer, which is how
+    /// buffers, windows and tabpages are represented.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub(crate) fn from_handle(ty: ObjectKind, handle: Integer) -> Self {
+        Self { ty, data: ObjectData { integer: handle } }
+    }
+
     #[inline]
     pub fn kind(&self) -> ObjectKind {
         self.ty
     }
 
+    /// Returns a borrowed view into the object which can be matched on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nvim_types::{Object, ObjectRef};
+    ///
+    /// fn count_strings(obj: &Object) -> usize {
+    ///     match obj.view() {
+    ///         ObjectRef::String(_) => 1,
+    ///         ObjectRef::Array(arr) => arr.iter().map(count_strings).sum(),
+    ///         ObjectRef::Dictionary(dict) => {
+    ///             dict.iter().map(|pair| count_strings(pair.value())).sum()
+    ///         },
+    ///         _ => 0,
+    ///     }
+    /// }
+    ///
+    /// let obj = Object::from_iter([Object::from("foo"), Object::from(42)]);
+    /// assert_eq!(1, count_strings(&obj));
+    /// ```
+    pub fn view(&self) -> ObjectRef<'_> {
+        use ObjectKind::*;
+        unsafe {
+            match self.ty {
+                Nil => ObjectRef::Nil,
+                Boolean => ObjectRef::Boolean(self.data.boolean),
+                Integer => ObjectRef::Integer(self.data.integer),
+                Float => ObjectRef::Float(self.data.float),
+                String => ObjectRef::String(&self.data.string),
+                Array => ObjectRef::Array(&self.data.array),
+                Dictionary => ObjectRef::Dictionary(&self.data.dictionary),
+                LuaRef => ObjectRef::LuaRef(self.data.luaref),
+                Buffer => ObjectRef::Buffer(self.data.integer),
+                Window => ObjectRef::Window(self.data.integer),
+                TabPage => ObjectRef::TabPage(self.data.integer),
+            }
+        }
+    }
+
+    /// Returns a mutable view into the object which can be matched on to
+    /// modify its contents in place.
+    pub fn view_mut(&mut self) -> ObjectMut<'_> {
+        use ObjectKind::*;
+        unsafe {
+            match self.ty {
+                Nil => ObjectMut::Nil,
+                Boolean => ObjectMut::Boolean(&mut self.data.boolean),
+                Integer => ObjectMut::Integer(&mut self.data.integer),
+                Float => ObjectMut::Float(&mut self.data.float),
+                String => ObjectMut::String(&mut self.data.string),
+                Array => ObjectMut::Array(&mut self.data.array),
+                Dictionary => ObjectMut::Dictionary(&mut self.data.dictionary),
+                LuaRef => ObjectMut::LuaRef(&mut self.data.luaref),
+                Buffer => ObjectMut::Buffer(&mut self.data.integer),
+                Window => ObjectMut::Window(&mut self.data.integer),
+                TabPage => ObjectMut::TabPage(&mut self.data.integer),
+            }
+        }
+    }
+
+    /// Returns the contained boolean, or `None` if the object isn't a
+    /// boolean.
+    #[inline]
+    pub fn as_bool(&self) -> Option<Boolean> {
+        matches!(self.ty, ObjectKind::Boolean)
+            .then(|| unsafe { self.as_boolean_unchecked() })
+    }
+
+    /// Returns the contained integer, or `None` if the object isn't an
+    /// integer. Buffers, windows and tabpages are not considered integers.
+    #[inline]
+    pub fn as_integer(&self) -> Option<Integer> {
+        matches!(self.ty, ObjectKind::Integer)
+            .then(|| unsafe { self.as_integer_unchecked() })
+    }
+
+    /// Returns the contained float, or `None` if the object isn't a float.
+    #[inline]
+    pub fn as_float(&self) -> Option<Float> {
+        matches!(self.ty, ObjectKind::Float)
+            .then(|| unsafe { self.as_float_unchecked() })
+    }
+
+    /// Returns the contained [`String`](crate::String), or `None` if the
+    /// object isn't a string.
+    #[inline]
+    pub fn as_string(&self) -> Option<&crate::String> {
+        matches!(self.ty, ObjectKind::String)
+            .then(|| unsafe { self.as_string_unchecked() })
+    }
+
+    /// Returns the contained string as a `&str`, or `None` if the object
+    /// isn't a string or if it isn't valid UTF-8.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        self.as_string().and_then(|string| string.as_str().ok())
+    }
+
+    /// Returns the contained [`Array`], or `None` if the object isn't an
+    /// array.
+    #[inline]
+    pub fn as_array(&self) -> Option<&Array> {
+        matches!(self.ty, ObjectKind::Array)
+            .then(|| unsafe { self.as_array_unchecked() })
+    }
+
+    /// Returns the contained [`Dictionary`], or `None` if the object isn't a
+    /// dictionary.
+    #[inline]
+    pub fn as_dict(&self) -> Option<&Dictionary> {
+        matches!(self.ty, ObjectKind::Dictionary)
+            .then(|| unsafe { self.as_dict_unchecked() })
+    }
+
+    /// Returns the contained Lua reference, or `None` if the object isn't a
+    /// reference to a Lua function.
+    #[inline]
+    pub fn as_luaref(&self) -> Option<LuaRef> {
+        matches!(self.ty, ObjectKind::LuaRef)
+            .then(|| unsafe { self.as_luaref_unchecked() })
+    }
+
+    /// Same as [`as_string`](Object::as_string) but returns a mutable
+    /// reference.
+    #[inline]
+    pub fn as_string_mut(&mut self) -> Option<&mut crate::String> {
+        match self.view_mut() {
+            ObjectMut::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Same as [`as_array`](Object::as_array) but returns a mutable reference.
+    #[inline]
+    pub fn as_array_mut(&mut self) -> Option<&mut Array> {
+        match self.view_mut() {
+            ObjectMut::Array(array) => Some(array),
+            _ => None,
+        }
+    }
+
+    /// Same as [`as_dict`](Object::as_dict) but returns a mutable reference.
+    #[inline]
+    pub fn as_dict_mut(&mut self) -> Option<&mut Dictionary> {
+        match self.view_mut() {
+            ObjectMut::Dictionary(dict) => Some(dict),
+            _ => None,
+        }
+    }
+
     /// Make a non-owning version of this `Object`.
     #[inline]
     #[doc(hidden)]
@@ -162,6 +356,28 @@ impl Object {
         self.data.luaref
     }
 
+    /// Returns a reference to the contained [`String`](crate::String) value
+    /// without checking that the object actually contains a
+    /// [`String`](crate::String).
+    #[inline(always)]
+    pub unsafe fn as_string_unchecked(&self) -> &crate::String {
+        &self.data.string
+    }
+
+    /// Returns a reference to the contained [`Array`] value without checking
+    /// that the object actually contains an [`Array`].
+    #[inline(always)]
+    pub unsafe fn as_array_unchecked(&self) -> &Array {
+        &self.data.array
+    }
+
+    /// Returns a reference to the contained [`Dictionary`] value without
+    /// checking that the object actually contains a [`Dictionary`].
+    #[inline(always)]
+    pub unsafe fn as_dict_unchecked(&self) -> &Dictionary {
+        &self.data.dictionary
+    }
+
     /// Extracts the contained [`String`](crate::String) value without checking
     /// that the object actually contains a [`String`](crate::String).
     pub unsafe fn into_string_unchecked(self) -> crate::String {
@@ -466,7 +682,7 @@ impl Poppable for Object {
 
             LUA_TTABLE => {
                 if lua::utils::is_table_array(lstate, -1) {
-                    Array::pop(lstate).map(Into::into)
+                    <Array as Poppable>::pop(lstate).map(Into::into)
                 } else {
                     Dictionary::pop(lstate).map(Into::into)
                 }
@@ -546,11 +762,16 @@ mod serde {
                     Integer::try_from(n).map(Object::from).map_err(E::custom)
                 }
 
-                fn visit_f32<E>(self, f: f32) -> Result<Self::Value, E>
+                // Only reached for Lua references, see
+                // `Deserializer::deserialize_any`.
+                fn visit_newtype_struct<D>(
+                    self,
+                    deserializer: D,
+                ) -> Result<Self::Value, D::Error>
                 where
-                    E: de::Error,
+                    D: de::Deserializer<'de>,
                 {
-                    Ok(Object::from_luaref(f as LuaRef))
+                    LuaRef::deserialize(deserializer).map(Object::from_luaref)
                 }
 
                 fn visit_seq<A>(
@@ -600,6 +821,7 @@ mod serde {
                 visit_into!(visit_i32, i32);
                 visit_into!(visit_u32, u32);
                 visit_into!(visit_i64, i64);
+                visit_into!(visit_f32, f32);
                 visit_into!(visit_f64, f64);
                 visit_into!(visit_str, &str);
             }
@@ -614,6 +836,24 @@ mod tests {
     use super::*;
     use crate::conversion::FromObject;
 
+    #[test]
+    fn view_and_checked_accessors() {
+        let obj = Object::from("foo");
+        assert_eq!(Some("foo"), obj.as_str());
+        assert_eq!(None, obj.as_integer());
+        assert!(matches!(obj.view(), ObjectRef::String(s) if s == "foo"));
+
+        let mut obj = Object::from(Array::from((1, "bar")));
+        assert_eq!(None, obj.as_dict());
+
+        if let ObjectMut::Array(arr) = obj.view_mut() {
+            if let ObjectMut::Integer(n) = arr[0].view_mut() {
+                *n += 1;
+            }
+        }
+        assert_eq!(Some(2), obj.as_array().unwrap()[0].as_integer());
+    }
+
     #[test]
     fn std_string_to_obj_and_back() {
         let str = String::from("foo");
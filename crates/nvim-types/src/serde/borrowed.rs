@@ -0,0 +1,483 @@
+use std::slice;
+
+use serde::de::value::{BorrowedBytesDeserializer, BorrowedStrDeserializer};
+use serde::de::{self, IntoDeserializer};
+
+use super::{Error, Result};
+use crate::{KeyValuePair, Object, ObjectKind};
+
+/// A struct used for deserializing Rust values from a reference to a Neovim
+/// `Object`.
+///
+/// Unlike [`Deserializer`](super::Deserializer) this doesn't consume the
+/// object, and strings are passed to the visitors as borrowed slices. This
+/// allows deserializing types with `&'de str` or `&'de [u8]` fields which
+/// point directly into the object's memory without copying it.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::{serde::BorrowedDeserializer, Object};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Mark<'a> {
+///     name: &'a str,
+///     line: usize,
+/// }
+///
+/// let obj: Object = ..;
+/// let mark = Mark::deserialize(BorrowedDeserializer::new(&obj))?;
+/// ```
+pub struct BorrowedDeserializer<'de> {
+    obj: &'de Object,
+}
+
+impl<'de> BorrowedDeserializer<'de> {
+    pub fn new(obj: &'de Object) -> Self {
+        Self { obj }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for BorrowedDeserializer<'de> {
+    type Error = Error;
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf unit unit_struct identifier ignored_any
+    }
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        use ObjectKind::*;
+        let kind = self.obj.kind();
+        let result =
+            match kind {
+                Nil => visitor.visit_unit(),
+
+                Boolean => visitor
+                    .visit_bool(unsafe { self.obj.as_boolean_unchecked() }),
+
+                Integer | Buffer | Window | TabPage => visitor
+                    .visit_i64(unsafe { self.obj.as_integer_unchecked() }),
+
+                Float => unsafe {
+                    visitor.visit_f64(self.obj.as_float_unchecked())
+                },
+
+                String => {
+                    let string = unsafe { self.obj.as_string_unchecked() };
+                    match string.as_str() {
+                        Ok(str) => visitor.visit_borrowed_str(str),
+                        _ => visitor.visit_borrowed_bytes(string.as_bytes()),
+                    }
+                },
+
+                Array => self.deserialize_seq(visitor),
+
+                Dictionary => self.deserialize_map(visitor),
+
+                // See the comment in `Deserializer::deserialize_any`.
+                LuaRef => {
+                    let lua_ref = unsafe { self.obj.as_luaref_unchecked() };
+                    visitor.visit_newtype_struct(lua_ref.into_deserializer())
+                },
+            };
+
+        // Visitors don't know about objects so they can't tell what they got.
+        result.map_err(|err| err.with_found(kind))
+    }
+
+    #[inline]
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.obj.kind() {
+            ObjectKind::Nil => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    #[inline]
+    fn deserialize_enum<V>(
+        self,
+        _name: &str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let (variant, obj) = match self.obj.kind() {
+            ObjectKind::Dictionary => {
+                let dict = unsafe { self.obj.as_dict_unchecked() };
+
+                let KeyValuePair { key, value } = match &**dict {
+                    [pair] => pair,
+                    _ => {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Map,
+                            &"dictionary with a single key-value pair",
+                        ))
+                    },
+                };
+
+                (key.as_str()?, Some(value))
+            },
+
+            ObjectKind::String => {
+                (unsafe { self.obj.as_string_unchecked() }.as_str()?, None)
+            },
+
+            ty => {
+                return Err(Error::mismatched_kind("string or dictionary", ty))
+            },
+        };
+
+        visitor.visit_enum(EnumDeserializer { variant, obj })
+    }
+
+    #[inline]
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.obj.kind() {
+            ObjectKind::Array => {
+                let iter = unsafe { self.obj.as_array_unchecked() }.iter();
+                let mut deserializer = SeqDeserializer { iter, idx: 0 };
+                visitor.visit_seq(&mut deserializer)
+            },
+
+            ty => Err(Error::mismatched_kind("array", ty)),
+        }
+    }
+
+    #[inline]
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.obj.kind() {
+            ObjectKind::Dictionary => {
+                let iter = unsafe { self.obj.as_dict_unchecked() }.iter();
+                let mut deserializer = MapDeserializer { iter, pair: None };
+                visitor.visit_map(&mut deserializer)
+            },
+
+            ty => Err(Error::mismatched_kind("dictionary", ty)),
+        }
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match super::newtype_handle(self.obj, name)? {
+            Some(handle) => {
+                visitor.visit_newtype_struct(handle.into_deserializer())
+            },
+            None => visitor.visit_newtype_struct(self),
+        }
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for &'de Object {
+    type Deserializer = BorrowedDeserializer<'de>;
+
+    #[inline]
+    fn into_deserializer(self) -> Self::Deserializer {
+        BorrowedDeserializer::new(self)
+    }
+}
+
+struct SeqDeserializer<'de> {
+    iter: slice::Iter<'de, Object>,
+    idx: usize,
+}
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if let Some(obj) = self.iter.next() {
+            let idx = self.idx;
+            self.idx += 1;
+            return seed
+                .deserialize(BorrowedDeserializer { obj })
+                .map(Some)
+                .map_err(|err| err.at_index(idx));
+        }
+
+        Ok(None)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer<'de> {
+    iter: slice::Iter<'de, KeyValuePair>,
+    pair: Option<&'de KeyValuePair>,
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if let Some(pair) = self.iter.next() {
+            self.pair = Some(pair);
+            let key = &pair.key;
+
+            return match key.as_str() {
+                Ok(str) => seed.deserialize(BorrowedStrDeserializer::new(str)),
+                _ => seed.deserialize(BorrowedBytesDeserializer::new(
+                    key.as_bytes(),
+                )),
+            }
+            .map(Some);
+        }
+
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.pair.take() {
+            Some(KeyValuePair { key, value }) => seed
+                .deserialize(BorrowedDeserializer { obj: value })
+                .map_err(|err| err.at_key(&key.to_string_lossy())),
+            _ => Err(de::Error::custom("object is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct EnumDeserializer<'de> {
+    variant: &'de str,
+    obj: Option<&'de Object>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumDeserializer<'de> {
+    type Error = Error;
+    type Variant = VariantDeserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = BorrowedStrDeserializer::new(self.variant);
+        let deserializer = VariantDeserializer { obj: self.obj };
+        seed.deserialize(variant).map(|v| (v, deserializer))
+    }
+}
+
+struct VariantDeserializer<'de> {
+    obj: Option<&'de Object>,
+}
+
+impl<'de> de::VariantAccess<'de> for VariantDeserializer<'de> {
+    type Error = Error;
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.obj {
+            Some(obj) => seed.deserialize(BorrowedDeserializer { obj }),
+
+            // Unit variants are represented as strings.
+            _ => Err(Error::mismatched_kind(
+                "newtype variant",
+                ObjectKind::String,
+            )),
+        }
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.obj {
+            Some(obj) => de::Deserializer::deserialize_map(
+                BorrowedDeserializer { obj },
+                visitor,
+            ),
+
+            // Unit variants are represented as strings.
+            _ => Err(Error::mismatched_kind(
+                "struct variant",
+                ObjectKind::String,
+            )),
+        }
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.obj {
+            Some(obj) => de::Deserializer::deserialize_seq(
+                BorrowedDeserializer { obj },
+                visitor,
+            ),
+
+            // Unit variants are represented as strings.
+            _ => Err(Error::mismatched_kind(
+                "tuple variant",
+                ObjectKind::String,
+            )),
+        }
+    }
+
+    fn unit_variant(self) -> Result<()> {
+        match self.obj {
+            None => Ok(()),
+
+            Some(obj) => {
+                Err(Error::mismatched_kind("unit variant", obj.kind()))
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{Array, Dictionary};
+
+    #[test]
+    fn borrow_str() {
+        let obj = Object::from("foo");
+        let s = <&str>::deserialize(BorrowedDeserializer::new(&obj)).unwrap();
+        assert_eq!("foo", s);
+        assert_eq!(
+            unsafe { obj.as_string_unchecked() }.as_ptr(),
+            s.as_ptr() as _
+        );
+    }
+
+    #[test]
+    fn borrow_struct() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Mark<'a> {
+            name: &'a str,
+            line: usize,
+            #[serde(borrow)]
+            tags: Vec<&'a str>,
+        }
+
+        let obj = Object::from(Dictionary::from_iter([
+            ("name", Object::from("a")),
+            ("line", Object::from(3)),
+            ("tags", Object::from(Array::from(("foo", "bar")))),
+        ]));
+
+        assert_eq!(
+            Ok(Mark { name: "a", line: 3, tags: vec!["foo", "bar"] }),
+            Mark::deserialize(BorrowedDeserializer::new(&obj))
+        );
+
+        // The object is still usable.
+        assert_eq!(ObjectKind::Dictionary, obj.kind());
+    }
+
+    #[test]
+    fn borrow_map_keys() {
+        let obj =
+            Object::from(Dictionary::from_iter([("foo", 1), ("bar", 2)]));
+
+        let map = std::collections::BTreeMap::<&str, u8>::deserialize(
+            BorrowedDeserializer::new(&obj),
+        )
+        .unwrap();
+
+        assert_eq!(Some(&1), map.get("foo"));
+        assert_eq!(Some(&2), map.get("bar"));
+    }
+
+    #[test]
+    fn borrow_enum() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Kind<'a> {
+            Unit,
+            Named(&'a str),
+        }
+
+        let unit = Object::from("Unit");
+        let named = Object::from(Dictionary::from_iter([(
+            "Named",
+            Object::from("x"),
+        )]));
+
+        assert_eq!(
+            Ok(Kind::Unit),
+            Kind::deserialize(BorrowedDeserializer::new(&unit))
+        );
+        assert_eq!(
+            Ok(Kind::Named("x")),
+            Kind::deserialize(BorrowedDeserializer::new(&named))
+        );
+    }
+}
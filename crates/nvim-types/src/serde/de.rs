@@ -1,8 +1,9 @@
 use std::string::String as StdString;
 
+use serde::de::value::{BytesDeserializer, StrDeserializer};
 use serde::de::{self, IntoDeserializer};
 
-use super::Result;
+use super::{Error, Result};
 use crate::{Object, ObjectKind};
 
 /// A struct used for deserializing Neovim `Object`s into Rust values.
@@ -30,41 +31,44 @@ impl<'de> de::Deserializer<'de> for Deserializer {
         V: de::Visitor<'de>,
     {
         use ObjectKind::*;
-        match self.obj.kind() {
-            Nil => visitor.visit_unit(),
-
-            Boolean => {
-                visitor.visit_bool(unsafe { self.obj.as_boolean_unchecked() })
-            },
-
-            Integer | Buffer | Window | TabPage => {
-                visitor.visit_i64(unsafe { self.obj.as_integer_unchecked() })
-            },
-
-            Float => unsafe {
-                visitor.visit_f64(self.obj.as_float_unchecked())
-            },
-
-            String => {
-                let string = unsafe { self.obj.into_string_unchecked() };
-                match string.as_str() {
-                    Ok(str) => visitor.visit_str(str),
-                    _ => visitor.visit_bytes(string.as_bytes()),
-                }
-            },
-
-            Array => self.deserialize_seq(visitor),
-
-            Dictionary => self.deserialize_map(visitor),
-
-            // We map the ref representing the index of the lua function in the
-            // Lua registry to `f32`. It's definitely a hack, but Neovim rarely
-            // returns a float so it should a good place to store it to avoid
-            // collisions.
-            LuaRef => unsafe {
-                visitor.visit_f32(self.obj.as_luaref_unchecked() as f32)
-            },
-        }
+        let kind = self.obj.kind();
+        let result =
+            match kind {
+                Nil => visitor.visit_unit(),
+
+                Boolean => visitor
+                    .visit_bool(unsafe { self.obj.as_boolean_unchecked() }),
+
+                Integer | Buffer | Window | TabPage => visitor
+                    .visit_i64(unsafe { self.obj.as_integer_unchecked() }),
+
+                Float => unsafe {
+                    visitor.visit_f64(self.obj.as_float_unchecked())
+                },
+
+                String => {
+                    let string = unsafe { self.obj.into_string_unchecked() };
+                    match string.as_str() {
+                        Ok(str) => visitor.visit_str(str),
+                        _ => visitor.visit_bytes(string.as_bytes()),
+                    }
+                },
+
+                Array => self.deserialize_seq(visitor),
+
+                Dictionary => self.deserialize_map(visitor),
+
+                // Lua references are serialized as the `LUA_REF_NEWTYPE`
+                // newtype, so they're only handed out wrapped in a newtype.
+                // Visitors of plain numbers will reject them.
+                LuaRef => {
+                    let lua_ref = unsafe { self.obj.as_luaref_unchecked() };
+                    visitor.visit_newtype_struct(lua_ref.into_deserializer())
+                },
+            };
+
+        // Visitors don't know about objects so they can't tell what they got.
+        result.map_err(|err| err.with_found(kind))
     }
 
     #[inline]
@@ -111,7 +115,9 @@ impl<'de> de::Deserializer<'de> for Deserializer {
                 None,
             ),
 
-            _ => return Err(de::Error::custom("bad enum value")),
+            ty => {
+                return Err(Error::mismatched_kind("string or dictionary", ty))
+            },
         };
 
         visitor.visit_enum(EnumDeserializer { variant, obj })
@@ -126,14 +132,11 @@ impl<'de> de::Deserializer<'de> for Deserializer {
             ObjectKind::Array => {
                 let iter =
                     unsafe { self.obj.into_array_unchecked() }.into_iter();
-                let mut deserializer = SeqDeserializer { iter };
+                let mut deserializer = SeqDeserializer { iter, idx: 0 };
                 visitor.visit_seq(&mut deserializer)
             },
 
-            ty => Err(de::Error::invalid_type(
-                de::Unexpected::Other(&format!("{ty:?}")),
-                &"array",
-            )),
+            ty => Err(Error::mismatched_kind("array", ty)),
         }
     }
 
@@ -167,14 +170,11 @@ impl<'de> de::Deserializer<'de> for Deserializer {
             ObjectKind::Dictionary => {
                 let iter =
                     unsafe { self.obj.into_dict_unchecked() }.into_iter();
-                let mut deserializer = MapDeserializer { iter, obj: None };
+                let mut deserializer = MapDeserializer { iter, pair: None };
                 visitor.visit_map(&mut deserializer)
             },
 
-            ty => Err(de::Error::invalid_type(
-                de::Unexpected::Other(&format!("{ty:?}")),
-                &"dictionary",
-            )),
+            ty => Err(Error::mismatched_kind("dictionary", ty)),
         }
     }
 
@@ -194,18 +194,24 @@ impl<'de> de::Deserializer<'de> for Deserializer {
     #[inline]
     fn deserialize_newtype_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         visitor: V,
     ) -> Result<V::Value>
     where
         V: de::Visitor<'de>,
     {
-        visitor.visit_newtype_struct(self)
+        match super::newtype_handle(&self.obj, name)? {
+            Some(handle) => {
+                visitor.visit_newtype_struct(handle.into_deserializer())
+            },
+            None => visitor.visit_newtype_struct(self),
+        }
     }
 }
 
 struct SeqDeserializer {
     iter: crate::ArrayIterator,
+    idx: usize,
 }
 
 impl<'de> de::SeqAccess<'de> for SeqDeserializer {
@@ -216,7 +222,12 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer {
         T: de::DeserializeSeed<'de>,
     {
         if let Some(obj) = self.iter.next() {
-            return seed.deserialize(Deserializer { obj }).map(Some);
+            let idx = self.idx;
+            self.idx += 1;
+            return seed
+                .deserialize(Deserializer { obj })
+                .map(Some)
+                .map_err(|err| err.at_index(idx));
         }
 
         Ok(None)
@@ -229,7 +240,7 @@ impl<'de> de::SeqAccess<'de> for SeqDeserializer {
 
 struct MapDeserializer {
     iter: crate::DictIterator,
-    obj: Option<Object>,
+    pair: Option<(crate::String, Object)>,
 }
 
 impl<'de> de::MapAccess<'de> for MapDeserializer {
@@ -240,10 +251,14 @@ impl<'de> de::MapAccess<'de> for MapDeserializer {
         K: de::DeserializeSeed<'de>,
     {
         if let Some((name, obj)) = self.iter.next() {
-            self.obj = Some(obj);
-            return seed
-                .deserialize(Deserializer { obj: name.into() })
-                .map(Some);
+            // The key is kept around to be included in the path of the
+            // errors returned while deserializing the value.
+            let key = match name.as_str() {
+                Ok(str) => seed.deserialize(StrDeserializer::new(str)),
+                _ => seed.deserialize(BytesDeserializer::new(name.as_bytes())),
+            };
+            self.pair = Some((name, obj));
+            return key.map(Some);
         }
 
         Ok(None)
@@ -253,8 +268,10 @@ impl<'de> de::MapAccess<'de> for MapDeserializer {
     where
         V: de::DeserializeSeed<'de>,
     {
-        match self.obj.take() {
-            Some(obj) => seed.deserialize(Deserializer { obj }),
+        match self.pair.take() {
+            Some((name, obj)) => seed
+                .deserialize(Deserializer { obj })
+                .map_err(|err| err.at_key(&name.to_string_lossy())),
             _ => Err(de::Error::custom("object is missing")),
         }
     }
@@ -297,9 +314,10 @@ impl<'de> de::VariantAccess<'de> for VariantDeserializer {
         match self.obj {
             Some(obj) => seed.deserialize(Deserializer { obj }),
 
-            _ => Err(de::Error::invalid_type(
-                de::Unexpected::UnitVariant,
-                &"newtype variant",
+            // Unit variants are represented as strings.
+            _ => Err(Error::mismatched_kind(
+                "newtype variant",
+                ObjectKind::String,
             )),
         }
     }
@@ -318,9 +336,10 @@ impl<'de> de::VariantAccess<'de> for VariantDeserializer {
                 visitor,
             ),
 
-            _ => Err(de::Error::invalid_type(
-                de::Unexpected::UnitVariant,
-                &"struct variant",
+            // Unit variants are represented as strings.
+            _ => Err(Error::mismatched_kind(
+                "struct variant",
+                ObjectKind::String,
             )),
         }
     }
@@ -335,9 +354,10 @@ impl<'de> de::VariantAccess<'de> for VariantDeserializer {
                 visitor,
             ),
 
-            _ => Err(de::Error::invalid_type(
-                de::Unexpected::UnitVariant,
-                &"tuple variant",
+            // Unit variants are represented as strings.
+            _ => Err(Error::mismatched_kind(
+                "tuple variant",
+                ObjectKind::String,
             )),
         }
     }
@@ -346,10 +366,9 @@ impl<'de> de::VariantAccess<'de> for VariantDeserializer {
         match self.obj {
             None => Ok(()),
 
-            _ => Err(de::Error::invalid_type(
-                de::Unexpected::NewtypeVariant,
-                &"unit variant",
-            )),
+            Some(obj) => {
+                Err(Error::mismatched_kind("unit variant", obj.kind()))
+            },
         }
     }
 }
@@ -404,4 +423,134 @@ mod tests {
         ]);
         assert_eq!(Ok(Object::from(map.clone())), d(map));
     }
+
+    #[test]
+    fn deserialize_handle_newtypes() {
+        use serde::Serialize;
+
+        use crate::serde::Serializer;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        #[serde(rename = "$__nvim_oxi_Buffer")]
+        struct Buffer(i32);
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Foo {
+            buf: Buffer,
+            n: i32,
+        }
+
+        let foo = Foo { buf: Buffer(3), n: 3 };
+        let obj = foo.serialize(Serializer::new()).unwrap();
+        let dict = unsafe { obj.as_dict_unchecked() };
+        assert_eq!(
+            Some(ObjectKind::Buffer),
+            dict.get(&"buf").map(Object::kind)
+        );
+        assert_eq!(
+            Some(ObjectKind::Integer),
+            dict.get(&"n").map(Object::kind)
+        );
+
+        assert_eq!(Ok(foo), Foo::deserialize(Deserializer::new(obj)));
+
+        // Plain integers are accepted too, but other kinds aren't.
+        assert_eq!(
+            Ok(Buffer(1)),
+            Buffer::deserialize(Deserializer::new(1.into()))
+        );
+        let win = Object::from_handle(ObjectKind::Window, 1);
+        assert!(Buffer::deserialize(Deserializer::new(win)).is_err());
+    }
+
+    #[test]
+    fn deserialize_function_newtype() {
+        use serde::Serialize;
+
+        use crate::serde::Serializer;
+        use crate::Function;
+
+        let fun = Function::<(), ()>::from_ref(42);
+        let obj = fun.serialize(Serializer::new()).unwrap();
+        assert_eq!(Object::from_luaref(42), obj);
+
+        let fun = Function::<(), ()>::deserialize(Deserializer::new(obj));
+        assert_eq!(Ok(42), fun.map(|f| f.lua_ref));
+    }
+
+    #[test]
+    fn deserialize_luaref_only_as_newtype() {
+        let obj = Object::from_luaref(42);
+        assert_eq!(Ok(obj.clone()), d(obj.clone()));
+        assert!(f64::deserialize(Deserializer::new(obj.clone())).is_err());
+        assert!(i32::deserialize(Deserializer::new(obj)).is_err());
+
+        // Floats stay floats.
+        assert_eq!(Ok(Object::from(1.5f64)), d(1.5f32));
+    }
+
+    #[test]
+    fn error_path_and_kinds() {
+        #[derive(Debug, Deserialize)]
+        struct Config {
+            #[allow(dead_code)]
+            ui: Ui,
+        }
+
+        #[derive(Debug, Deserialize)]
+        struct Ui {
+            #[allow(dead_code)]
+            border: Vec<Border>,
+        }
+
+        #[derive(Debug, Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Border {
+            #[allow(dead_code)]
+            hl_group: String,
+        }
+
+        let border = |hl_group: Object| {
+            Object::from(Dictionary::from_iter([("hl_group", hl_group)]))
+        };
+
+        let config = |border: Array| {
+            Object::from(Dictionary::from_iter([(
+                "ui",
+                Dictionary::from_iter([("border", border)]),
+            )]))
+        };
+
+        let obj = config(Array::from_iter([
+            border("Normal".into()),
+            border("Normal".into()),
+            border(42.into()),
+        ]));
+
+        let err = Config::deserialize(Deserializer::new(obj)).unwrap_err();
+        assert_eq!(Some("ui.border[2].hl_group"), err.path());
+        assert_eq!(
+            &super::Error::InvalidType {
+                expected: "a string".into(),
+                found: Some(ObjectKind::Integer),
+            },
+            err.inner()
+        );
+        assert_eq!(
+            "ui.border[2].hl_group: invalid type: expected a string, found \
+             integer",
+            err.to_string()
+        );
+
+        let obj =
+            config(Array::from_iter([Object::from(Dictionary::from_iter([
+                ("hl_gruop", "Normal"),
+            ]))]));
+
+        let err = Config::deserialize(Deserializer::new(obj)).unwrap_err();
+        assert_eq!(
+            "ui.border[0]: unknown field `hl_gruop`, did you mean `hl_group`?",
+            err.to_string()
+        );
+    }
 }
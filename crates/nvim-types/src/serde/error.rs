@@ -3,6 +3,8 @@ use std::fmt;
 use serde::{de, ser};
 use thiserror::Error as ThisError;
 
+use crate::ObjectKind;
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Clone, Debug, Eq, PartialEq, ThisError)]
@@ -13,11 +15,101 @@ pub enum Error {
     #[error("{0}")]
     Deserialize(String),
 
+    /// The object doesn't have the kind expected by the type being
+    /// deserialized.
+    #[error(
+        "invalid type: expected {expected}, found {}",
+        .found.map_or("unknown", |kind| kind.as_static())
+    )]
+    InvalidType { expected: String, found: Option<ObjectKind> },
+
+    /// A dictionary contains a key that doesn't match any field of the struct
+    /// being deserialized, which is only an error when the struct is marked
+    /// with `#[serde(deny_unknown_fields)]`.
+    #[error("unknown field `{field}`, {}", expected_fields(.suggestion, .expected))]
+    UnknownField {
+        field: String,
+        suggestion: Option<&'static str>,
+        expected: &'static [&'static str],
+    },
+
+    /// An error that happened while deserializing a value nested inside
+    /// dictionaries and/or arrays, e.g. at `ui.border[2].hl_group`.
+    #[error("{path}: {source}")]
+    Path { path: String, source: Box<Error> },
+
     #[error(transparent)]
     FromInt(#[from] std::num::TryFromIntError),
 
     #[error(transparent)]
     FromUtf8(#[from] std::string::FromUtf8Error),
+
+    #[error(transparent)]
+    Utf8(#[from] std::str::Utf8Error),
+}
+
+impl Error {
+    /// The path of the value that caused the error, e.g.
+    /// `ui.border[2].hl_group`, or `None` if the error happened at the top
+    /// level.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            Self::Path { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The error without the path information.
+    pub fn inner(&self) -> &Self {
+        match self {
+            Self::Path { source, .. } => source,
+            other => other,
+        }
+    }
+
+    /// Creates an [`Error::InvalidType`] for an object whose kind is known.
+    pub(super) fn mismatched_kind(expected: &str, found: ObjectKind) -> Self {
+        Self::InvalidType { expected: expected.to_owned(), found: Some(found) }
+    }
+
+    /// Records that the error happened while deserializing the value of the
+    /// key `key`.
+    pub(super) fn at_key(self, key: &str) -> Self {
+        self.prepend(key.to_owned())
+    }
+
+    /// Records that the error happened while deserializing the `idx`-th
+    /// element of an array.
+    pub(super) fn at_index(self, idx: usize) -> Self {
+        self.prepend(format!("[{idx}]"))
+    }
+
+    /// Fills in the kind of the object that failed to deserialize if the
+    /// error was returned by a visitor, which doesn't know about objects.
+    pub(super) fn with_found(self, kind: ObjectKind) -> Self {
+        match self {
+            Self::InvalidType { expected, found: None } => {
+                Self::InvalidType { expected, found: Some(kind) }
+            },
+            other => other,
+        }
+    }
+
+    fn prepend(self, mut segment: String) -> Self {
+        let (path, source) = match self {
+            Self::Path { path, source } => (path, source),
+            other => {
+                return Self::Path { path: segment, source: Box::new(other) }
+            },
+        };
+
+        if !path.starts_with('[') {
+            segment.push('.');
+        }
+        segment.push_str(&path);
+
+        Self::Path { path: segment, source }
+    }
 }
 
 impl ser::Error for Error {
@@ -30,4 +122,104 @@ impl de::Error for Error {
     fn custom<T: fmt::Display>(msg: T) -> Self {
         Self::Deserialize(msg.to_string())
     }
+
+    fn invalid_type(_unexp: de::Unexpected, exp: &dyn de::Expected) -> Self {
+        Self::InvalidType { expected: exp.to_string(), found: None }
+    }
+
+    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
+        Self::UnknownField {
+            field: field.to_owned(),
+            suggestion: closest_match(field, expected),
+            expected,
+        }
+    }
+}
+
+/// Formats the second half of the message of [`Error::UnknownField`].
+fn expected_fields(suggestion: &Option<&str>, expected: &[&str]) -> String {
+    if let Some(suggestion) = suggestion {
+        return format!("did you mean `{suggestion}`?");
+    }
+
+    match expected {
+        [] => "there are no fields".to_owned(),
+        [field] => format!("expected `{field}`"),
+        fields => {
+            let fields = fields
+                .iter()
+                .map(|field| format!("`{field}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("expected one of {fields}")
+        },
+    }
+}
+
+/// Returns the candidate closest to `name`, as long as it's close enough to
+/// plausibly be a typo.
+fn closest_match(
+    name: &str,
+    candidates: &[&'static str],
+) -> Option<&'static str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+
+    candidates
+        .iter()
+        .map(|&candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edit_distances() {
+        assert_eq!(0, edit_distance("border", "border"));
+        assert_eq!(1, edit_distance("boder", "border"));
+        assert_eq!(2, edit_distance("hl_gruop", "hl_group"));
+        assert_eq!(6, edit_distance("", "border"));
+    }
+
+    #[test]
+    fn suggestions() {
+        let fields = &["border", "hl_group", "width"];
+        assert_eq!(Some("border"), closest_match("boder", fields));
+        assert_eq!(Some("hl_group"), closest_match("hlgroup", fields));
+        assert_eq!(None, closest_match("height", fields));
+    }
+
+    #[test]
+    fn nested_paths() {
+        let err = Error::Deserialize("foo".into())
+            .at_key("hl_group")
+            .at_index(2)
+            .at_key("border")
+            .at_key("ui");
+
+        assert_eq!(Some("ui.border[2].hl_group"), err.path());
+        assert_eq!("ui.border[2].hl_group: foo", err.to_string());
+    }
 }
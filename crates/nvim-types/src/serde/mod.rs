@@ -3,10 +3,74 @@
 //!
 //! [Serde]: https://serde.rs/
 
+mod borrowed;
 mod de;
 mod error;
 mod ser;
 
+pub use borrowed::BorrowedDeserializer;
 pub use de::Deserializer;
 pub use error::{Error, Result};
 pub use ser::Serializer;
+
+/// The name of the newtype struct buffers are (de)serialized as, which lets
+/// the (de)serializers in this module map them to [`ObjectKind::Buffer`]
+/// objects instead of integers.
+///
+/// [`ObjectKind::Buffer`]: crate::ObjectKind::Buffer
+#[doc(hidden)]
+pub const BUFFER_NEWTYPE: &str = "$__nvim_oxi_Buffer";
+
+/// Same as [`BUFFER_NEWTYPE`] but for windows.
+#[doc(hidden)]
+pub const WINDOW_NEWTYPE: &str = "$__nvim_oxi_Window";
+
+/// Same as [`BUFFER_NEWTYPE`] but for tabpages.
+#[doc(hidden)]
+pub const TABPAGE_NEWTYPE: &str = "$__nvim_oxi_TabPage";
+
+#[doc(hidden)]
+pub use luajit_bindings::serde::LUA_REF_NEWTYPE;
+
+use crate::{Integer, Object, ObjectKind};
+
+/// Returns the kind of the objects the values of the newtype struct called
+/// `name` are (de)serialized as, if it's one of the special ones above.
+fn newtype_kind(name: &str) -> Option<ObjectKind> {
+    match name {
+        BUFFER_NEWTYPE => Some(ObjectKind::Buffer),
+        WINDOW_NEWTYPE => Some(ObjectKind::Window),
+        TABPAGE_NEWTYPE => Some(ObjectKind::TabPage),
+        LUA_REF_NEWTYPE => Some(ObjectKind::LuaRef),
+        _ => None,
+    }
+}
+
+/// Returns the integer the deserializers should pass to the visitor of the
+/// newtype struct called `name`, or `None` if it's not one of the special
+/// ones above.
+///
+/// Integers are accepted for every kind since that's how Neovim represents
+/// buffers, windows and tabpages in most places.
+fn newtype_handle(obj: &Object, name: &str) -> Result<Option<Integer>> {
+    let kind = match newtype_kind(name) {
+        Some(kind) => kind,
+        None => return Ok(None),
+    };
+
+    match obj.kind() {
+        ObjectKind::LuaRef if kind == ObjectKind::LuaRef => {
+            Ok(Some(unsafe { obj.as_luaref_unchecked() }.into()))
+        },
+
+        ObjectKind::Integer if kind != ObjectKind::LuaRef => {
+            Ok(Some(unsafe { obj.as_integer_unchecked() }))
+        },
+
+        other if other == kind => {
+            Ok(Some(unsafe { obj.as_integer_unchecked() }))
+        },
+
+        other => Err(Error::mismatched_kind(kind.as_static(), other)),
+    }
+}
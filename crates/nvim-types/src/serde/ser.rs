@@ -2,7 +2,7 @@ use serde::ser::{self, Error};
 
 use super::Result;
 use crate::conversion::FromObject;
-use crate::Object;
+use crate::{LuaRef, Object, ObjectKind};
 
 /// A struct for serializing Rust values into Neovim `Object`s.
 #[non_exhaustive]
@@ -76,11 +76,9 @@ impl ser::Serializer for Serializer {
     serialize_nil!(serialize_none);
     serialize_nil!(serialize_unit);
 
-    // 32bit floats are serialized into references to Lua functions, as
-    // described in `super::de`.
     #[inline]
     fn serialize_f32(self, value: f32) -> Result<Self::Ok> {
-        Ok(Object::from_luaref(value as i32))
+        self.serialize_f64(value.into())
     }
 
     #[inline]
@@ -114,13 +112,37 @@ impl ser::Serializer for Serializer {
     #[inline]
     fn serialize_newtype_struct<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         value: &T,
     ) -> Result<Self::Ok>
     where
         T: ser::Serialize + ?Sized,
     {
-        value.serialize(self)
+        let obj = value.serialize(self)?;
+
+        let kind = match super::newtype_kind(name) {
+            Some(kind) => kind,
+            None => return Ok(obj),
+        };
+
+        match obj.kind() {
+            ObjectKind::Integer => {
+                let handle = unsafe { obj.as_integer_unchecked() };
+
+                Ok(match kind {
+                    ObjectKind::LuaRef => {
+                        Object::from_luaref(LuaRef::try_from(handle)?)
+                    },
+                    _ => Object::from_handle(kind, handle),
+                })
+            },
+
+            other => Err(Error::custom(format!(
+                "expected an integer inside the {} newtype, got {}",
+                kind.as_static(),
+                other.as_static(),
+            ))),
+        }
     }
 
     #[inline]
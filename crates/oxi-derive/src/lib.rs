@@ -0,0 +1,205 @@
+//! Derive macros for the conversion traits of `nvim-oxi`.
+//!
+//! The `FromObject` and `ToObject` impls generated by these macros go
+//! through `nvim_oxi::serde`, so the type also needs to implement serde's
+//! `Deserialize` and `Serialize`. This means that all of serde's attributes
+//! (`#[serde(rename = "..")]`, `#[serde(default)]`, `#[serde(skip)]`, etc.)
+//! can be used to customize how a type is converted.
+//!
+//! The `Pushable` and `Poppable` impls go through `nvim_oxi::lua::serde`
+//! instead, which reads and writes Lua values directly without allocating
+//! any intermediate `Object`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, DeriveInput, Generics};
+
+/// Derives `FromObject` for a type implementing serde's `Deserialize`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::conversion::FromObject;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, FromObject)]
+/// struct Point {
+///     x: i32,
+///
+///     #[serde(default)]
+///     y: i32,
+/// }
+/// ```
+#[proc_macro_derive(FromObject)]
+pub fn derive_from_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let ident = &input.ident;
+
+    let generics = with_bound(
+        &input.generics,
+        quote!(::nvim_oxi::__private::serde::de::DeserializeOwned),
+    );
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::nvim_oxi::conversion::FromObject
+            for #ident #ty_generics #where_clause
+        {
+            fn from_object(
+                obj: ::nvim_oxi::Object,
+            ) -> ::std::result::Result<Self, ::nvim_oxi::conversion::Error> {
+                <Self as ::nvim_oxi::__private::serde::Deserialize>::deserialize(
+                    ::nvim_oxi::serde::Deserializer::new(obj),
+                )
+                .map_err(::std::convert::Into::into)
+            }
+        }
+    }
+    .into()
+}
+
+/// Derives `ToObject` for a type implementing serde's `Serialize`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::conversion::ToObject;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, ToObject)]
+/// #[serde(rename_all = "snake_case")]
+/// enum Mode {
+///     Normal,
+///     Insert,
+/// }
+/// ```
+#[proc_macro_derive(ToObject)]
+pub fn derive_to_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let ident = &input.ident;
+
+    let generics = with_bound(
+        &input.generics,
+        quote!(::nvim_oxi::__private::serde::Serialize),
+    );
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::nvim_oxi::conversion::ToObject
+            for #ident #ty_generics #where_clause
+        {
+            fn to_object(
+                self,
+            ) -> ::std::result::Result<
+                ::nvim_oxi::Object,
+                ::nvim_oxi::conversion::Error,
+            > {
+                ::nvim_oxi::__private::serde::Serialize::serialize(
+                    &self,
+                    ::nvim_oxi::serde::Serializer::new(),
+                )
+                .map_err(::std::convert::Into::into)
+            }
+        }
+    }
+    .into()
+}
+
+/// Derives `Poppable` for a type implementing serde's `Deserialize`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua::Poppable;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Poppable)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+/// ```
+#[proc_macro_derive(Poppable)]
+pub fn derive_poppable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let ident = &input.ident;
+
+    let generics = with_bound(
+        &input.generics,
+        quote!(::nvim_oxi::__private::serde::de::DeserializeOwned),
+    );
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::nvim_oxi::lua::Poppable
+            for #ident #ty_generics #where_clause
+        {
+            unsafe fn pop(
+                lstate: *mut ::nvim_oxi::lua::ffi::lua_State,
+            ) -> ::std::result::Result<Self, ::nvim_oxi::lua::Error> {
+                ::nvim_oxi::lua::serde::pop(lstate)
+            }
+        }
+    }
+    .into()
+}
+
+/// Derives `Pushable` for a type implementing serde's `Serialize`.
+///
+/// # Examples
+///
+/// ```ignore
+/// use nvim_oxi::lua::Pushable;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize, Pushable)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+/// ```
+#[proc_macro_derive(Pushable)]
+pub fn derive_pushable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let ident = &input.ident;
+
+    let generics = with_bound(
+        &input.generics,
+        quote!(::nvim_oxi::__private::serde::Serialize),
+    );
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::nvim_oxi::lua::Pushable
+            for #ident #ty_generics #where_clause
+        {
+            unsafe fn push(
+                self,
+                lstate: *mut ::nvim_oxi::lua::ffi::lua_State,
+            ) -> ::std::result::Result<::std::ffi::c_int, ::nvim_oxi::lua::Error>
+            {
+                ::nvim_oxi::lua::serde::push(lstate, &self)
+            }
+        }
+    }
+    .into()
+}
+
+/// Returns a copy of `generics` where `Self` is bounded by `bound`. This
+/// lets generic types get the impl as long as their type parameters allow it.
+fn with_bound(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+
+    generics.make_where_clause().predicates.push(parse_quote!(Self: #bound));
+
+    generics
+}
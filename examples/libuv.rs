@@ -2,9 +2,8 @@ use std::thread;
 use std::time::Duration;
 
 use nvim_oxi as oxi;
-use oxi::libuv::{AsyncHandle, TimerHandle};
+use oxi::libuv::{self, Sender, TimerHandle};
 use oxi::print;
-use tokio::sync::mpsc::{self, UnboundedSender};
 use tokio::time;
 
 #[oxi::module]
@@ -33,16 +32,13 @@ fn libuv() -> oxi::Result<()> {
     // --
     let msg = String::from("Hey there!");
 
-    let _handle = TimerHandle::once(Duration::from_secs(2), move || {
+    let _ = TimerHandle::once(Duration::from_secs(2), move || {
         oxi::schedule(move |_| Ok(print!("{msg}")));
         Ok::<_, oxi::Error>(())
     });
 
     // --
-    let (sender, mut receiver) = mpsc::unbounded_channel::<i32>();
-
-    let handle = AsyncHandle::new(move || {
-        let i = receiver.blocking_recv().unwrap();
+    let sender = libuv::channel(move |i: i32| {
         oxi::schedule(move |_| {
             print!("Received number {i} from backround thread");
             Ok(())
@@ -50,18 +46,17 @@ fn libuv() -> oxi::Result<()> {
         Ok::<_, oxi::Error>(())
     })?;
 
-    let _ = thread::spawn(move || send_numbers(handle, sender));
+    let _ = thread::spawn(move || send_numbers(sender));
 
     Ok(())
 }
 
 #[tokio::main]
-async fn send_numbers(handle: AsyncHandle, sender: UnboundedSender<i32>) {
+async fn send_numbers(sender: Sender<i32>) {
     let mut i = 0;
 
     loop {
         sender.send(i).unwrap();
-        handle.send().unwrap();
         i += 1;
 
         time::sleep(Duration::from_secs(1)).await;
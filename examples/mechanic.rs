@@ -1,9 +1,9 @@
-use nvim_oxi::{self as oxi, api, lua, print, Dictionary, Function, Object};
-use oxi::conversion::{self, FromObject, ToObject};
-use oxi::serde::{Deserializer, Serializer};
+use nvim_oxi::{self as oxi, api, print, Dictionary, Function};
+use oxi::conversion::{FromObject, ToObject};
+use oxi::lua::{Poppable, Pushable};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, FromObject, ToObject, Poppable, Pushable)]
 struct Car {
     manufacturer: CarManufacturer,
 
@@ -35,39 +35,6 @@ enum CarProblem {
     Pollutes,
 }
 
-impl FromObject for Car {
-    fn from_object(obj: Object) -> Result<Self, conversion::Error> {
-        Self::deserialize(Deserializer::new(obj)).map_err(Into::into)
-    }
-}
-
-impl ToObject for Car {
-    fn to_object(self) -> Result<Object, conversion::Error> {
-        self.serialize(Serializer::new()).map_err(Into::into)
-    }
-}
-
-impl lua::Poppable for Car {
-    unsafe fn pop(
-        lstate: *mut lua::ffi::lua_State,
-    ) -> Result<Self, lua::Error> {
-        let obj = Object::pop(lstate)?;
-        Self::from_object(obj)
-            .map_err(lua::Error::pop_error_from_err::<Self, _>)
-    }
-}
-
-impl lua::Pushable for Car {
-    unsafe fn push(
-        self,
-        lstate: *mut lua::ffi::lua_State,
-    ) -> Result<std::ffi::c_int, lua::Error> {
-        self.to_object()
-            .map_err(lua::Error::push_error_from_err::<Self, _>)?
-            .push(lstate)
-    }
-}
-
 fn fix(mut car: Car) -> oxi::Result<Car> {
     if car.works {
         return Ok(car);
@@ -0,0 +1,146 @@
+use nvim_oxi::conversion::{FromObject, ToObject};
+use nvim_oxi::lua::{with_state, Poppable, Pushable};
+use nvim_oxi::{self as oxi, api, Dictionary, Function, Object, ObjectKind};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    FromObject,
+    ToObject,
+    Poppable,
+    Pushable,
+)]
+struct Config {
+    #[serde(rename = "name")]
+    title: String,
+
+    #[serde(default)]
+    width: u32,
+
+    #[serde(skip)]
+    cached: Option<u32>,
+
+    mode: Mode,
+}
+
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    FromObject,
+    ToObject,
+    Poppable,
+    Pushable,
+)]
+#[serde(rename_all = "snake_case")]
+enum Mode {
+    ReadOnly,
+    ReadWrite,
+}
+
+#[oxi::test]
+fn derive_to_from_object() {
+    let config = Config {
+        title: "foo".into(),
+        width: 80,
+        cached: None,
+        mode: Mode::ReadOnly,
+    };
+
+    let obj = config.clone().to_object().unwrap();
+
+    let dict = Dictionary::from_object(obj.clone()).unwrap();
+    assert_eq!(Some(&Object::from("foo")), dict.get(&"name"));
+    assert_eq!(Some(&Object::from("read_only")), dict.get(&"mode"));
+    assert_eq!(None, dict.get(&"cached"));
+
+    assert_eq!(config, Config::from_object(obj).unwrap());
+}
+
+#[oxi::test]
+fn derive_from_object_default() {
+    let obj = Object::from(Dictionary::from_iter([
+        ("name", Object::from("foo")),
+        ("mode", Object::from("read_write")),
+    ]));
+
+    let config = Config::from_object(obj).unwrap();
+    assert_eq!(0, config.width);
+    assert_eq!(Mode::ReadWrite, config.mode);
+}
+
+#[oxi::test]
+fn derive_push_pop() {
+    let config = Config {
+        title: "bar".into(),
+        width: 100,
+        cached: None,
+        mode: Mode::ReadWrite,
+    };
+
+    let popped = unsafe {
+        with_state(|lstate| {
+            config.clone().push(lstate).unwrap();
+            Config::pop(lstate).unwrap()
+        })
+    };
+
+    assert_eq!(config, popped);
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Serialize, Deserialize, FromObject, ToObject,
+)]
+struct Handles {
+    buf: api::Buffer,
+    win: api::Window,
+    on_exit: Function<(), ()>,
+}
+
+#[oxi::test]
+fn derive_handles_to_from_object() {
+    let handles = Handles {
+        buf: api::Buffer::current(),
+        win: api::Window::current(),
+        on_exit: Function::from_fn(|()| Ok::<_, oxi::Error>(())),
+    };
+
+    let obj = handles.clone().to_object().unwrap();
+
+    let dict = Dictionary::from_object(obj.clone()).unwrap();
+    assert_eq!(Some(ObjectKind::Buffer), dict.get(&"buf").map(Object::kind));
+    assert_eq!(Some(ObjectKind::Window), dict.get(&"win").map(Object::kind));
+    assert_eq!(
+        Some(ObjectKind::LuaRef),
+        dict.get(&"on_exit").map(Object::kind)
+    );
+
+    assert_eq!(handles, Handles::from_object(obj).unwrap());
+}
+
+#[oxi::test]
+fn derive_handles_serde_push_pop() {
+    let handles = Handles {
+        buf: api::Buffer::current(),
+        win: api::Window::current(),
+        on_exit: Function::from_fn(|()| Ok::<_, oxi::Error>(())),
+    };
+
+    let popped = unsafe {
+        with_state(|lstate| {
+            oxi::lua::serde::push(lstate, &handles).unwrap();
+            oxi::lua::serde::pop::<Handles>(lstate).unwrap()
+        })
+    };
+
+    assert_eq!(handles.buf, popped.buf);
+    assert_eq!(handles.win, popped.win);
+    assert_eq!(Ok(()), popped.on_exit.call(()));
+}
@@ -1 +1,5 @@
 mod api;
+mod derive;
+#[cfg(feature = "libuv")]
+mod libuv;
+mod lua;
@@ -0,0 +1,97 @@
+use std::cell::{Cell, RefCell};
+use std::convert::Infallible;
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use nvim_oxi::{self as oxi, libuv, lua};
+
+/// Runs the Neovim event loop until `cond` returns `true` or `timeout_ms`
+/// milliseconds have passed, returning whether `cond` was satisfied.
+fn wait_for<F>(timeout_ms: u32, cond: F) -> bool
+where
+    F: Fn() -> bool + 'static,
+{
+    let cond =
+        lua::LuaFunction::from_fn(move |()| Ok::<_, Infallible>(cond()));
+    lua::call_path::<_, bool>("vim.wait", (timeout_ms, cond)).unwrap()
+}
+
+#[oxi::test]
+fn spawn_and_sleep() {
+    let output = Rc::new(Cell::new(None::<(i32, Duration)>));
+
+    let task = libuv::executor::spawn(async {
+        let start = Instant::now();
+        libuv::executor::sleep(Duration::from_millis(20)).await.unwrap();
+        (42, start.elapsed())
+    })
+    .unwrap();
+
+    {
+        let output = Rc::clone(&output);
+        let detached = libuv::executor::spawn(async move {
+            output.set(Some(task.await));
+        });
+        drop(detached.unwrap());
+    }
+
+    let done = Rc::clone(&output);
+    assert!(wait_for(1000, move || done.get().is_some()));
+
+    let (value, elapsed) = output.get().unwrap();
+    assert_eq!(42, value);
+    assert!(elapsed >= Duration::from_millis(20));
+}
+
+#[oxi::test]
+fn channel() {
+    let received = Rc::new(RefCell::new(Vec::new()));
+
+    let sender = {
+        let received = Rc::clone(&received);
+        libuv::channel(move |i: i32| {
+            received.borrow_mut().push(i);
+            Ok::<_, Infallible>(())
+        })
+        .unwrap()
+    };
+
+    thread::spawn(move || {
+        for i in 0..100 {
+            sender.send(i).unwrap();
+        }
+    })
+    .join()
+    .unwrap();
+
+    let done = Rc::clone(&received);
+    assert!(wait_for(1000, move || done.borrow().len() == 100));
+    assert_eq!(&(0..100).collect::<Vec<_>>(), received.borrow().as_slice());
+}
+
+#[oxi::test]
+fn once_handles_close_themselves() {
+    let before = libuv::live_handles();
+
+    libuv::TimerHandle::once(Duration::from_millis(1), || {
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+
+    let signum = lua::call_path::<_, lua::LuaFunction>(
+        "loadstring",
+        "return vim.loop.constants.SIGUSR1",
+    )
+    .and_then(|chunk| chunk.call::<_, i32>(()))
+    .unwrap();
+
+    libuv::SignalHandle::once(signum, |_| Ok::<_, Infallible>(())).unwrap();
+
+    assert_eq!(before + 2, libuv::live_handles());
+
+    let pid = lua::call_path::<_, i32>("vim.fn.getpid", ()).unwrap();
+    lua::call_path::<_, ()>("vim.loop.kill", (pid, "sigusr1")).unwrap();
+
+    assert!(wait_for(1000, move || libuv::live_handles() == before));
+}
@@ -0,0 +1,711 @@
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Debug;
+
+use nvim_oxi as oxi;
+use oxi::lua::{self, Poppable, Pushable};
+
+/// Pushes `value` on the Lua stack and pops it back, checking that it's equal
+/// to the original value and that the stack is left as it was. All the values
+/// pushed are popped together, since some types push more than one.
+fn round_trip<T>(value: T)
+where
+    T: Pushable + Poppable + Clone + Debug + PartialEq,
+{
+    unsafe {
+        lua::with_state(|lstate| {
+            let top = lua::ffi::lua_gettop(lstate);
+            let nret = value.clone().push(lstate).unwrap();
+            let popped = T::pop_many(lstate, nret).unwrap();
+            assert_eq!(value, popped);
+            assert_eq!(top, lua::ffi::lua_gettop(lstate));
+        })
+    }
+}
+
+#[oxi::test]
+fn round_trip_tuples() {
+    round_trip((1i32,));
+    round_trip((1i32, String::from("foo")));
+    round_trip((true, 2.5f64, String::from("bar"), Some(3u8)));
+}
+
+#[oxi::test]
+fn round_trip_maps() {
+    round_trip(HashMap::from([
+        (String::from("foo"), 1i32),
+        (String::from("bar"), 2),
+    ]));
+    round_trip(BTreeMap::from([(1i32, true), (2, false)]));
+    round_trip(HashMap::<String, i32>::new());
+}
+
+#[oxi::test]
+fn round_trip_sets() {
+    round_trip(HashSet::from([1i32, 2, 3]));
+    round_trip(BTreeSet::from([String::from("foo"), String::from("bar")]));
+}
+
+#[oxi::test]
+fn round_trip_arrays() {
+    round_trip([1i32, 2, 3]);
+    round_trip(vec![String::from("foo"), String::from("bar")]);
+}
+
+#[oxi::test]
+fn pop_array_wrong_length() {
+    unsafe {
+        lua::with_state(|lstate| {
+            vec![1i32, 2].push(lstate).unwrap();
+            assert!(<[i32; 3]>::pop(lstate).is_err());
+        })
+    }
+}
+
+#[oxi::test]
+fn round_trip_boxes_and_cows() {
+    round_trip(Box::new(42i32));
+    round_trip(Cow::<'static, str>::Owned(String::from("foo")));
+}
+
+#[oxi::test]
+fn round_trip_results() {
+    round_trip(Ok::<_, String>(42i32));
+    round_trip(Err::<i32, _>(String::from("oops")));
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Item {
+    label: String,
+    kind: Option<Kind>,
+    tags: Vec<String>,
+    score: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Kind {
+    Keyword,
+    Snippet(String),
+    Field { depth: u8 },
+}
+
+/// Serializes `value` onto the Lua stack and deserializes it back.
+fn serde_round_trip<T>(value: T)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Debug + PartialEq,
+{
+    unsafe {
+        lua::with_state(|lstate| {
+            let top = lua::ffi::lua_gettop(lstate);
+            lua::serde::push(lstate, &value).unwrap();
+            let popped = lua::serde::pop::<T>(lstate).unwrap();
+            assert_eq!(value, popped);
+            assert_eq!(top, lua::ffi::lua_gettop(lstate));
+        })
+    }
+}
+
+#[oxi::test]
+fn serde_round_trip_structs() {
+    serde_round_trip(Item {
+        label: "foo".into(),
+        kind: None,
+        tags: vec![],
+        score: 1.5,
+    });
+
+    serde_round_trip(vec![
+        Item {
+            label: "bar".into(),
+            kind: Some(Kind::Keyword),
+            tags: vec!["a".into(), "b".into()],
+            score: 0.0,
+        },
+        Item {
+            label: "baz".into(),
+            kind: Some(Kind::Snippet("$1".into())),
+            tags: vec![],
+            score: -2.25,
+        },
+        Item {
+            label: "qux".into(),
+            kind: Some(Kind::Field { depth: 3 }),
+            tags: vec!["c".into()],
+            score: 42.0,
+        },
+    ]);
+}
+
+#[oxi::test]
+fn serde_round_trip_maps() {
+    serde_round_trip(HashMap::from([
+        (String::from("foo"), vec![1i64, 2, 3]),
+        (String::from("bar"), vec![]),
+    ]));
+    serde_round_trip((1u8, String::from("foo"), true, ()));
+}
+
+#[oxi::test]
+fn serde_functions_only_as_luarefs() {
+    use lua::ffi::*;
+
+    unsafe {
+        lua::with_state(|lstate| {
+            // Freeing a reference makes it the next one handed out by
+            // `luaL_ref`, which lets us check that nothing else took it.
+            lua_pushboolean(lstate, 1);
+            let free = luaL_ref(lstate, LUA_REGISTRYINDEX);
+            luaL_unref(lstate, LUA_REGISTRYINDEX, free);
+
+            let top = lua_gettop(lstate);
+            lua::LuaFunction::from_fn(|()| Ok::<_, lua::Error>(()))
+                .push(lstate)
+                .unwrap();
+            assert!(lua::serde::pop::<f64>(lstate).is_err());
+            assert_eq!(top, lua_gettop(lstate));
+
+            lua_pushboolean(lstate, 1);
+            let next = luaL_ref(lstate, LUA_REGISTRYINDEX);
+            luaL_unref(lstate, LUA_REGISTRYINDEX, next);
+            assert_eq!(free, next);
+        })
+    }
+}
+
+#[oxi::test]
+fn serde_nan_map_keys() {
+    /// A map with a single NaN key, since floats can't be `HashMap` keys.
+    struct NanKey;
+
+    impl serde::Serialize for NanKey {
+        fn serialize<S: serde::Serializer>(
+            &self,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_map([(f64::NAN, 1)])
+        }
+    }
+
+    unsafe {
+        lua::with_state(|lstate| {
+            let top = lua::ffi::lua_gettop(lstate);
+            assert!(lua::serde::push(lstate, &NanKey).is_err());
+            assert_eq!(top, lua::ffi::lua_gettop(lstate));
+        })
+    }
+}
+
+#[oxi::test]
+fn serde_push_table_layout() {
+    use lua::ffi::*;
+
+    unsafe {
+        lua::with_state(|lstate| {
+            let item = Item {
+                label: "foo".into(),
+                kind: Some(Kind::Field { depth: 7 }),
+                tags: vec![],
+                score: 0.5,
+            };
+
+            lua::serde::push(lstate, &item).unwrap();
+
+            lua_getfield(lstate, -1, "label\0".as_ptr() as *const _);
+            assert_eq!(
+                Ok(String::from("foo")),
+                <String as Poppable>::pop(lstate)
+            );
+
+            // Struct variants are externally tagged.
+            lua_getfield(lstate, -1, "kind\0".as_ptr() as *const _);
+            lua_getfield(lstate, -1, "Field\0".as_ptr() as *const _);
+            lua_getfield(lstate, -1, "depth\0".as_ptr() as *const _);
+            assert_eq!(Ok(7), u8::pop(lstate));
+
+            lua_pop(lstate, 3);
+        })
+    }
+}
+
+#[oxi::test]
+fn serde_pop_wrong_type() {
+    unsafe {
+        lua::with_state(|lstate| {
+            let top = lua::ffi::lua_gettop(lstate);
+            String::from("foo").push(lstate).unwrap();
+            assert!(lua::serde::pop::<Item>(lstate).is_err());
+            assert_eq!(top, lua::ffi::lua_gettop(lstate));
+        })
+    }
+}
+
+#[oxi::test]
+fn lua_table_get_set() {
+    let table = lua::LuaTable::new();
+    assert!(table.is_empty());
+
+    table.set("foo", 42).unwrap();
+    table.set(1, "bar").unwrap();
+    table.set(2, true).unwrap();
+
+    assert_eq!(Ok(42), table.get::<_, i32>("foo"));
+    assert_eq!(Ok(String::from("bar")), table.get::<_, String>(1));
+    assert_eq!(Ok(None), table.get::<_, Option<i32>>("baz"));
+    assert_eq!(2, table.len());
+    assert!(!table.is_empty());
+
+    assert!(table.set((), 1).is_err());
+
+    // Setting a key to nil removes it.
+    table.set("foo", ()).unwrap();
+    assert_eq!(Ok(lua::LuaValue::Nil), table.get("foo"));
+}
+
+#[oxi::test]
+fn lua_table_pairs_ipairs() {
+    let table = lua::LuaTable::new();
+    table.set(1, "a").unwrap();
+    table.set(2, "b").unwrap();
+    table.set(4, "d").unwrap();
+    table.set("foo", "bar").unwrap();
+
+    let values = table.ipairs::<String>().collect::<Result<Vec<_>, _>>();
+    assert_eq!(Ok(vec![String::from("a"), String::from("b")]), values);
+
+    let mut pairs = table
+        .pairs::<lua::LuaValue, String>()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    pairs.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    assert_eq!(
+        vec![
+            (lua::LuaValue::Number(1.0), String::from("a")),
+            (lua::LuaValue::Number(2.0), String::from("b")),
+            (lua::LuaValue::from("foo"), String::from("bar")),
+            (lua::LuaValue::Number(4.0), String::from("d")),
+        ],
+        pairs
+    );
+}
+
+#[oxi::test]
+fn lua_table_metatable() {
+    let table = lua::LuaTable::new();
+    assert_eq!(None, table.metatable());
+
+    let metatable = lua::LuaTable::new();
+    table.set_metatable(Some(&metatable));
+    assert_eq!(Some(metatable), table.metatable());
+
+    table.set_metatable(None);
+    assert_eq!(None, table.metatable());
+}
+
+#[oxi::test]
+fn lua_value_round_trip() {
+    let config = lua::LuaTable::new();
+
+    let on_attach =
+        lua::LuaFunction::from_fn(|n: i32| Ok::<_, lua::Error>(n * 2));
+    let nested = lua::LuaTable::new();
+    nested.set("enabled", true).unwrap();
+
+    config.set("on_attach", on_attach).unwrap();
+    config.set("nested", nested.clone()).unwrap();
+
+    let value = lua::LuaValue::Table(config);
+    round_trip(value.clone());
+
+    let config = value.as_table().unwrap();
+
+    let on_attach = config.get::<_, lua::LuaFunction>("on_attach").unwrap();
+    assert_eq!(Ok(42), on_attach.call::<_, i32>(21));
+
+    let got = config.get::<_, lua::LuaTable>("nested").unwrap();
+    assert_eq!(nested, got);
+    assert_eq!(Ok(true), got.get::<_, bool>("enabled"));
+}
+
+#[oxi::test]
+fn call_path() {
+    assert_eq!(Ok(5), lua::call_path::<_, i32>("math.max", (1, 5, 3)));
+
+    assert_eq!(
+        Ok(String::from("ababab")),
+        lua::call_path::<_, String>("string.rep", ("ab", 3))
+    );
+
+    // Lazily loaded modules are resolved.
+    assert!(lua::call_path::<_, bool>("vim.lsp.buf.server_ready", ()).is_ok());
+}
+
+#[oxi::test]
+fn call_path_missing() {
+    let err = lua::call_path::<_, ()>("vim.foo.bar", ()).unwrap_err();
+
+    match err {
+        lua::Error::RuntimeError { message, traceback } => {
+            assert!(message.contains("'vim.foo' is nil"), "{message}");
+            assert!(traceback.is_some());
+        },
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[oxi::test]
+fn call_path_error() {
+    let err = lua::call_path::<_, ()>("error", "oops").unwrap_err();
+
+    match err {
+        lua::Error::RuntimeError { message, traceback } => {
+            assert_eq!("oops", message);
+            assert!(traceback.unwrap().contains("[C]: in function 'error'"));
+        },
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[oxi::test]
+fn require() {
+    let inspect = lua::require::<lua::LuaTable>("vim.inspect").unwrap();
+    assert!(inspect.metatable().is_some());
+
+    assert!(lua::require::<lua::LuaValue>("not_a_module").is_err());
+}
+
+#[oxi::test]
+fn function_call_traceback() {
+    let fun = lua::LuaFunction::from_fn(|()| {
+        Err::<(), _>(lua::Error::pop_error("foo", "bar"))
+    });
+
+    let err = fun.call::<_, ()>(()).unwrap_err();
+
+    match err {
+        lua::Error::RuntimeError { traceback, .. } => {
+            assert!(traceback.is_some())
+        },
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[oxi::test]
+fn function_panic_is_caught() {
+    let fun = lua::LuaFunction::from_fn(|()| -> Result<(), lua::Error> {
+        panic!("boom")
+    });
+
+    let err = fun.call::<_, ()>(()).unwrap_err();
+
+    match err {
+        lua::Error::RuntimeError { message, .. } => {
+            assert!(message.contains("panicked at 'boom'"), "{message}");
+            assert!(message.contains(file!()), "{message}");
+        },
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+
+    // Neovim is still alive.
+    assert_eq!(Ok(2), lua::call_path::<_, i32>("math.abs", -2));
+}
+
+#[oxi::test]
+fn variadic_args() {
+    let fun = lua::LuaFunction::from_fn(
+        |(sep, words): (String, lua::Variadic<String>)| {
+            Ok::<_, lua::Error>(words.join(&sep))
+        },
+    );
+
+    assert_eq!(Ok(String::from("a-b-c")), fun.call(("-", "a", "b", "c")));
+    assert_eq!(Ok(String::new()), fun.call("-"));
+}
+
+#[oxi::test]
+fn variadic_table_values() {
+    let table =
+        load("return { 1, 2, 3 }").call::<_, lua::LuaTable>(()).unwrap();
+
+    // A table value is a single value, the table below it is left alone.
+    assert_eq!(
+        Ok(lua::Variadic::from(vec![2])),
+        table.get::<_, lua::Variadic<i32>>(2)
+    );
+
+    let nested = load("return { { 1 }, { 2 } }")
+        .call::<_, Vec<lua::Variadic<Vec<i32>>>>(());
+    assert_eq!(Ok(vec![vec![vec![1]].into(), vec![vec![2]].into()]), nested);
+}
+
+#[oxi::test]
+fn multiple_return_values() {
+    let fun = lua::LuaFunction::from_fn(|n: i32| {
+        let ret: lua::MultiValue = if n > 0 {
+            vec![lua::LuaValue::Number(n as f64)].into()
+        } else {
+            vec![lua::LuaValue::Nil, "not positive".into()].into()
+        };
+        Ok::<_, lua::Error>(ret)
+    });
+
+    assert_eq!(
+        Ok((Some(1), None)),
+        fun.call::<_, (Option<i32>, Option<String>)>(1)
+    );
+
+    assert_eq!(
+        Ok((None, Some(String::from("not positive")))),
+        fun.call::<_, (Option<i32>, Option<String>)>(-1)
+    );
+
+    let all =
+        lua::call_path::<_, lua::MultiValue>("string.byte", ("abc", 1, 3));
+    assert_eq!(3, all.unwrap().len());
+}
+
+#[oxi::test]
+fn result_args_and_returns() {
+    // The `nil` belongs to the `Option`, not to the `Result`.
+    let fun = lua::LuaFunction::from_fn(
+        |(name, n): (Option<String>, Result<i32, String>)| {
+            Ok::<_, lua::Error>((name.is_none(), n.unwrap_or(0)))
+        },
+    );
+
+    assert_eq!(Ok((true, 5)), fun.call::<_, (bool, i32)>((None::<String>, 5)));
+
+    assert_eq!(
+        Ok(Err(String::from("oops"))),
+        load("return nil, 'oops'").call::<_, Result<i32, String>>(())
+    );
+
+    assert_eq!(Ok(Ok(3)), load("return 3").call::<_, Result<i32, String>>(()));
+}
+
+#[oxi::test]
+fn result_errors_in_tables() {
+    unsafe {
+        lua::with_state(|lstate| {
+            let top = lua::ffi::lua_gettop(lstate);
+
+            let list: Vec<Result<i32, String>> =
+                vec![Ok(1), Err(String::from("oops"))];
+            assert!(list.push(lstate).is_err());
+            assert_eq!(top, lua::ffi::lua_gettop(lstate));
+
+            let map: HashMap<String, Result<i32, String>> = HashMap::from([(
+                String::from("a"),
+                Err(String::from("oops")),
+            )]);
+            assert!(map.push(lstate).is_err());
+            assert_eq!(top, lua::ffi::lua_gettop(lstate));
+
+            let ok: Vec<Result<i32, String>> = vec![Ok(1), Ok(2)];
+            assert_eq!(Ok(1), ok.push(lstate));
+            assert_eq!(Ok(vec![1, 2]), <Vec<i32> as Poppable>::pop(lstate));
+        })
+    }
+}
+
+struct Counter(u32);
+
+impl lua::UserData for Counter {
+    fn add_methods(methods: &mut lua::UserDataMethods<Self>) {
+        methods.add_method("get", |this, ()| Ok::<_, lua::Error>(this.0));
+
+        methods.add_method_mut("incr", |this, by: u32| {
+            this.0 += by;
+            Ok::<_, lua::Error>(())
+        });
+
+        methods.add_meta_method(
+            lua::MetaMethod::Index,
+            |this, key: String| {
+                Ok::<_, lua::Error>((key == "value").then_some(this.0))
+            },
+        );
+
+        methods.add_meta_method(lua::MetaMethod::ToString, |this, ()| {
+            Ok::<_, lua::Error>(format!("Counter({})", this.0))
+        });
+    }
+}
+
+/// Compiles a chunk of Lua code into a function.
+fn load(chunk: &str) -> lua::LuaFunction {
+    lua::call_path("loadstring", chunk).unwrap()
+}
+
+#[oxi::test]
+fn userdata_methods() {
+    let counter = lua::UserDataRef::new(Counter(0));
+
+    let chunk = load(
+        "local c = ...; c:incr(2); c:incr(3); return c:get(), c.value, \
+         c.foo, tostring(c)",
+    );
+
+    assert_eq!(
+        Ok((5, Some(5), None, String::from("Counter(5)"))),
+        chunk.call::<_, (u32, Option<u32>, Option<u32>, String)>(
+            counter.clone()
+        )
+    );
+
+    assert_eq!(5, counter.borrow().unwrap().0);
+}
+
+#[oxi::test]
+fn userdata_borrow_is_checked() {
+    let counter = lua::UserDataRef::new(Counter(0));
+
+    let guard = counter.borrow().unwrap();
+    let err =
+        load("(...):incr(1)").call::<_, ()>(counter.clone()).unwrap_err();
+    assert!(err.to_string().contains("couldn't be borrowed"), "{err}");
+    drop(guard);
+
+    assert!(load("(...):incr(1)").call::<_, ()>(counter.clone()).is_ok());
+    assert_eq!(1, counter.borrow().unwrap().0);
+}
+
+#[oxi::test]
+fn userdata_wrong_type() {
+    let err = load("return {}")
+        .call::<_, lua::UserDataRef<Counter>>(())
+        .unwrap_err();
+
+    assert!(matches!(err, lua::Error::PopError { .. }), "{err}");
+
+    // Calling a method with `.` instead of `:`.
+    let counter = lua::UserDataRef::new(Counter(0));
+    assert!(load("(...).get()").call::<_, ()>(counter).is_err());
+}
+
+#[oxi::test]
+fn userdata_manual_gc() {
+    let counter = lua::UserDataRef::new(Counter(0));
+
+    // The metatable is hidden from `getmetatable` but not from
+    // `debug.getmetatable`, so the metamethod can still be called manually.
+    let chunk = load(
+        "local c = ...; local hidden = getmetatable(c); local gc = \
+         debug.getmetatable(c).__gc; gc(c); gc(c); gc({}); return hidden",
+    );
+
+    assert_eq!(Ok(false), chunk.call::<_, bool>(counter.clone()));
+
+    let err = counter.borrow().err().expect("the value was dropped");
+    assert!(matches!(err, lua::Error::BorrowError { .. }), "{err}");
+    assert!(load("(...):incr(1)").call::<_, ()>(counter).is_err());
+}
+
+#[oxi::test]
+fn userdata_manual_gc_while_borrowed() {
+    let counter = lua::UserDataRef::new(Counter(0));
+
+    let gc = load("local c = ...; debug.getmetatable(c).__gc(c)");
+
+    let borrow = counter.borrow().unwrap();
+    assert!(gc.call::<_, ()>(counter.clone()).is_err());
+    assert_eq!(0, borrow.0);
+    drop(borrow);
+
+    // Once the borrow is gone the value can be dropped.
+    assert_eq!(Ok(()), gc.call::<_, ()>(counter.clone()));
+    assert!(counter.borrow().is_err());
+}
+
+#[oxi::test]
+fn thread_resume() {
+    let fun = load(
+        "local a = ...; local b = coroutine.yield(a + 1); return a + b, \
+         'done'",
+    );
+
+    let thread = lua::LuaThread::new(&fun);
+    assert_eq!(lua::ThreadStatus::Resumable, thread.status());
+
+    assert_eq!(Ok(2), thread.resume::<_, i32>(1));
+    assert_eq!(lua::ThreadStatus::Resumable, thread.status());
+
+    assert_eq!(
+        Ok((11, String::from("done"))),
+        thread.resume::<_, (i32, String)>(10)
+    );
+    assert_eq!(lua::ThreadStatus::Finished, thread.status());
+
+    assert!(thread.resume::<_, ()>(()).is_err());
+}
+
+#[oxi::test]
+fn thread_error() {
+    let thread = lua::LuaThread::new(&load("error('oops')"));
+
+    match thread.resume::<_, ()>(()).unwrap_err() {
+        lua::Error::RuntimeError { message, traceback } => {
+            assert!(message.ends_with("oops"), "{message}");
+            assert!(traceback.is_some());
+        },
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+
+    assert_eq!(lua::ThreadStatus::Error, thread.status());
+}
+
+#[oxi::test]
+fn thread_resume_running() {
+    let check = lua::LuaFunction::from_fn(|thread: lua::LuaThread| {
+        assert_eq!(lua::ThreadStatus::Running, thread.status());
+        assert!(thread.resume::<_, ()>(1).is_err());
+        Ok::<_, lua::Error>(())
+    });
+
+    // The coroutine checking itself.
+    let fun = load(
+        "local check = ...; local a = 40; check(coroutine.running()); return \
+         a + 2",
+    );
+    let thread = lua::LuaThread::new(&fun);
+    assert_eq!(Ok(42), thread.resume::<_, i32>(check.clone()));
+
+    // A coroutine checked by the one it resumed.
+    let fun = load(
+        "local check = ...; local outer = coroutine.running(); local a = 40; \
+         local inner = coroutine.create(function() check(outer) end); \
+         assert(coroutine.resume(inner)); return a + 2",
+    );
+    let thread = lua::LuaThread::new(&fun);
+    assert_eq!(Ok(42), thread.resume::<_, i32>(check));
+}
+
+#[oxi::test]
+fn thread_error_with_nul_bytes() {
+    let thread = lua::LuaThread::new(&load("error('foo\\0bar', 0)"));
+
+    match thread.resume::<_, ()>(()).unwrap_err() {
+        lua::Error::RuntimeError { message, traceback } => {
+            assert_eq!("foo\0bar", message);
+            assert!(traceback.is_some());
+        },
+        other => panic!("expected a runtime error, got {other:?}"),
+    }
+}
+
+#[oxi::test]
+fn rust_function_yields() {
+    let double = lua::LuaFunction::from_fn(|n: i32| {
+        Ok::<_, lua::Error>(lua::Yield(n * 2))
+    });
+
+    let fun = load("local double = ...; return double(21) + 1");
+    let thread = lua::LuaThread::new(&fun);
+
+    // The coroutine yields from inside the Rust function, and the value it's
+    // resumed with is returned to the Lua caller.
+    assert_eq!(Ok(42), thread.resume::<_, i32>(double));
+    assert_eq!(Ok(101), thread.resume::<_, i32>(100));
+    assert_eq!(lua::ThreadStatus::Finished, thread.status());
+}